@@ -0,0 +1,98 @@
+//! Renders a top-down preview of a world generator and prints chunk timings.
+//!
+//! ```sh
+//! cargo run --example generator_preview -- preview.png 8
+//! ```
+
+use std::collections::BTreeMap;
+
+use common::{
+    blocks::block_info::generate_block_id_map,
+    chunks::{
+        block_position::ChunkBlockPosition,
+        chunk_data::{BlockDataInfo, ChunkData, ChunkSectionData},
+        chunk_position::ChunkPosition,
+    },
+    default_blocks::generate_default_blocks,
+    default_blocks_ids::BlockID,
+    utils::colors::parse_to_terminal_colors,
+    world_generator::{
+        preview::{get_map_colors, GeneratorPreview},
+        traits::{IWorldGenerator, WorldGeneratorSettings},
+    },
+    CHUNK_SIZE, VERTICAL_SECTIONS,
+};
+
+/// Sine hills with water below the sea level
+struct HillsGenerator;
+
+const WATER_LEVEL: f32 = 58.0;
+
+impl IWorldGenerator for HillsGenerator {
+    fn generate_chunk_data(world_settings: &WorldGeneratorSettings, chunk_position: &ChunkPosition) -> ChunkData {
+        let mut random = world_settings.get_chunk_random(chunk_position, "hills");
+        let phase = (world_settings.get_seed() % 360) as f32;
+        let sand_level = WATER_LEVEL + random.range(0, 3) as f32;
+
+        let mut chunk_data = ChunkData::default();
+        for section in 0..VERTICAL_SECTIONS {
+            let mut section_data = ChunkSectionData::default();
+            for x in 0..CHUNK_SIZE {
+                for z in 0..CHUNK_SIZE {
+                    let x_map = (chunk_position.x * CHUNK_SIZE as i64 + x as i64) as f32;
+                    let z_map = (chunk_position.z * CHUNK_SIZE as i64 + z as i64) as f32;
+                    let surface = 60.0 + ((x_map + phase) * 0.05).sin() * 6.0 + (z_map * 0.03).cos() * 8.0;
+
+                    for y in 0..CHUNK_SIZE {
+                        let y_global = (section * CHUNK_SIZE as usize + y as usize) as f32;
+                        let block_id = if y_global < surface && surface < sand_level {
+                            BlockID::Sand.id()
+                        } else if y_global < surface {
+                            BlockID::Grass.id()
+                        } else if y_global < WATER_LEVEL {
+                            BlockID::Water.id()
+                        } else {
+                            continue;
+                        };
+                        section_data.insert(&ChunkBlockPosition::new(x, y, z), BlockDataInfo::create(block_id));
+                    }
+                }
+            }
+            chunk_data.push_section(section_data);
+        }
+        chunk_data
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let output = args.get(1).cloned().unwrap_or("preview.png".to_string());
+    let radius: i64 = args.get(2).and_then(|r| r.parse().ok()).unwrap_or(4);
+
+    let block_types = generate_default_blocks().unwrap();
+    let mut block_id_map: BTreeMap<u16, String> = Default::default();
+    generate_block_id_map(&mut block_id_map, block_types.iter()).unwrap();
+
+    let mut map_colors = get_map_colors(&block_id_map, block_types.iter());
+    map_colors.entry(BlockID::Water.id()).or_insert([50, 90, 200]);
+    map_colors.entry(BlockID::Sand.id()).or_insert([220, 205, 150]);
+
+    let settings = WorldGeneratorSettings::create(42, "hills", None, Default::default());
+    let preview = match GeneratorPreview::run::<HillsGenerator>(
+        &settings,
+        &ChunkPosition::new(-radius, -radius),
+        &ChunkPosition::new(radius, radius),
+    ) {
+        Ok(p) => p,
+        Err(e) => {
+            println!("{}", parse_to_terminal_colors(&e));
+            return;
+        }
+    };
+
+    let debug_info = preview.get_debug_info().get_console_print(3, "&7");
+    println!("{}", parse_to_terminal_colors(&debug_info));
+
+    std::fs::write(&output, preview.render(&map_colors).to_png()).unwrap();
+    println!("Preview saved to {}", output);
+}
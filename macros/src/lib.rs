@@ -31,7 +31,7 @@ pub fn event_handler(_attr: TokenStream, item: TokenStream) -> TokenStream {
     let extra_lets: Vec<_> = extra_args
         .iter()
         .map(|(pat, ty)| {
-            quote! { let #pat: #ty = Default::default(); }
+            quote! { let #pat: #ty = <#ty as common::plugin_api::service::PluginService>::inject(); }
         })
         .collect();
     let extra_pats: Vec<_> = extra_args.iter().map(|(pat, _)| pat).collect();
@@ -44,49 +44,49 @@ pub fn event_handler(_attr: TokenStream, item: TokenStream) -> TokenStream {
     };
     let output_handling = if has_return {
         quote! {
-            match #inner_call {
-                Ok(val) => {
-                    let __output_str = common::serde_json::to_string(&val)
-                        .expect("Failed to serialize return value");
-                    common::extism_pdk::output(&__output_str)
-                        .expect("Failed to write output");
-                }
-                Err(e) => {
-                    common::extism_pdk::log!(
-                        common::extism_pdk::LogLevel::Error,
-                        "Event error: {:?}",
-                        e
-                    );
-                    return 1;
-                }
-            }
+            let __result = match #inner_call {
+                Ok(val) => match common::serde_json::to_value(&val) {
+                    Ok(value) => __HandlerResult::ok(value),
+                    Err(e) => __HandlerResult::err(__HandlerErrorKind::Serialize, e.to_string()),
+                },
+                Err(e) => __HandlerResult::err(__HandlerErrorKind::Handler, format!("{:?}", e)),
+            };
         }
     } else {
         quote! {
             #inner_call;
+            let __result = __HandlerResult::ok(common::serde_json::Value::Null);
         }
     };
     let expanded = quote! {
+        // Fails to compile if the event type isn't a plugin event
+        const _: fn() = || {
+            fn __assert_plugin_event<T: common::plugin_api::events::PluginEvent>() {}
+            __assert_plugin_event::<#event_type>();
+        };
+
         #[no_mangle]
         #fn_vis extern "C" fn #fn_name() -> i32 {
+            use common::plugin_api::handler_result::{
+                HandlerErrorKind as __HandlerErrorKind, HandlerResult as __HandlerResult,
+            };
+
             fn __inner_fn(#event_pat: #event_type, #(#extra_params),*) #fn_output #fn_block
             let __input: String = match common::extism_pdk::input() {
                 Ok(s) => s,
                 Err(e) => {
-                    common::extism_pdk::log!(common::extism_pdk::LogLevel::Error, "Input error: {:?}", e);
-                    return 1;
+                    return __HandlerResult::err(__HandlerErrorKind::Input, e.to_string()).output();
                 }
             };
             let #event_pat: #event_type = match common::serde_json::from_str(&__input) {
                 Ok(e) => e,
                 Err(e) => {
-                    common::extism_pdk::log!(common::extism_pdk::LogLevel::Error, "Deserialize error: {:?}", e);
-                    return 2;
+                    return __HandlerResult::err(__HandlerErrorKind::Deserialize, e.to_string()).output();
                 }
             };
             #(#extra_lets)*
             #output_handling
-            0
+            __result.output()
         }
     };
     TokenStream::from(expanded)
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+use super::{block_info::BlockFace, block_shape::ShapeBox};
+
+#[serde_inline_default]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct LiquidProperties {
+    /// Upward force relative to the gravity; 1.0 keeps the body floating
+    #[serde_inline_default(1.0)]
+    pub buoyancy: f32,
+
+    /// Part of the velocity lost per second
+    #[serde_inline_default(0.8)]
+    pub drag: f32,
+}
+
+#[serde_inline_default]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct DamageProperties {
+    pub amount: f32,
+
+    /// Seconds between the hits while the body touches the block
+    #[serde_inline_default(1.0)]
+    pub interval: f32,
+}
+
+/// Physics properties of the block on top of the [`ColliderType`](super::block_type::ColliderType).
+///
+/// ```yaml
+/// collider:
+///   climbable: true
+///   friction: 0.1
+///   boxes:
+///     - min: [0.0, 0.0, 0.875]
+///       max: [1.0, 1.0, 1.0]
+/// ```
+#[serde_inline_default]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BlockCollider {
+    /// Boxes of the unrotated block; by default the block shape is used
+    #[serde(default)]
+    pub boxes: Option<Vec<ShapeBox>>,
+
+    /// Ladders and vines
+    #[serde(default)]
+    pub climbable: bool,
+
+    #[serde(default)]
+    pub liquid: Option<LiquidProperties>,
+
+    #[serde(default)]
+    pub damage: Option<DamageProperties>,
+
+    /// Multiplier of the surface friction, lower is more slippery
+    #[serde_inline_default(1.0)]
+    pub friction: f32,
+
+    /// Part of the velocity kept after the landing, from 0.0 to 1.0
+    #[serde(default)]
+    pub bounciness: f32,
+}
+
+impl Default for BlockCollider {
+    fn default() -> Self {
+        Self {
+            boxes: None,
+            climbable: false,
+            liquid: None,
+            damage: None,
+            friction: 1.0,
+            bounciness: 0.0,
+        }
+    }
+}
+
+impl BlockCollider {
+    /// Boxes may go outside of the block, like the fence ones, but must not be empty.
+    ///
+    /// Damage interval must be positive, otherwise the damage is dealt every physics step.
+    pub fn is_valid(&self) -> bool {
+        let non_negative = |v: f32| v.is_finite() && v >= 0.0;
+        let boxes = self.boxes.iter().flatten().all(|b| (0..3).all(|i| b.min[i] < b.max[i]));
+        let liquid = self
+            .liquid
+            .is_none_or(|l| non_negative(l.buoyancy) && non_negative(l.drag));
+        let damage = self
+            .damage
+            .is_none_or(|d| non_negative(d.amount) && d.interval.is_finite() && d.interval > 0.0);
+        boxes && liquid && damage && non_negative(self.friction) && (0.0..=1.0).contains(&self.bounciness)
+    }
+
+    pub fn get_rotated_boxes(&self, rotation: &Option<BlockFace>) -> Option<Vec<ShapeBox>> {
+        let boxes = self.boxes.as_ref()?;
+        Some(boxes.iter().map(|b| b.rotate(rotation)).collect())
+    }
+}
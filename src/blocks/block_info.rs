@@ -42,6 +42,16 @@ impl BlockFace {
         }
     }
 
+    /// Number of [`Self::rotate_left`] turns from `South`, which is the unrotated direction
+    pub fn get_turns(&self) -> u8 {
+        match *self {
+            BlockFace::South => 0,
+            BlockFace::West => 1,
+            BlockFace::North => 2,
+            BlockFace::East => 3,
+        }
+    }
+
     pub fn get_rotation(&self) -> Rotation {
         match *self {
             BlockFace::East => Rotation::new(0.0, 270.0),
@@ -0,0 +1,221 @@
+//! Partial cube shapes made of axis aligned boxes.
+//!
+//! Box coordinates are in block units from 0.0 to 1.0; the shape of the unrotated
+//! block is turned with the [`BlockFace`] yaw like the face textures.
+use serde::{Deserialize, Serialize};
+
+use super::{block_info::BlockFace, block_type::CubeFace};
+
+/// Mask of the [`BlockShape::get_full_faces`] for the full cube
+pub const FULL_FACES: u8 = 0b11_1111;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ShapeBox {
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+impl ShapeBox {
+    pub const fn new(min: [f32; 3], max: [f32; 3]) -> Self {
+        Self { min, max }
+    }
+
+    pub const fn full() -> Self {
+        Self::new([0.0; 3], [1.0; 3])
+    }
+
+    pub fn is_valid(&self) -> bool {
+        (0..3).all(|i| 0.0 <= self.min[i] && self.min[i] < self.max[i] && self.max[i] <= 1.0)
+    }
+
+    /// Turns the box around the block center by the rotation yaw;
+    /// every [`BlockFace::rotate_left`] turn moves the +Z side to -X.
+    pub fn rotate(&self, rotation: &Option<BlockFace>) -> Self {
+        let turns = rotation.map_or(0, |r| r.get_turns());
+        let mut b = *self;
+        for _ in 0..turns {
+            b = Self::new(
+                [1.0 - b.max[2], b.min[1], b.min[0]],
+                [1.0 - b.min[2], b.max[1], b.max[0]],
+            );
+        }
+        b
+    }
+
+    /// Position of the box side on the normal axis of the face
+    pub fn get_face_depth(&self, face: CubeFace) -> f32 {
+        let axis = face.index() % 3;
+        match face.index() < 3 {
+            true => self.min[axis],
+            false => self.max[axis],
+        }
+    }
+
+    /// Min and max of the box side on the two other axes, in the X, Y, Z order
+    pub fn get_face_rect(&self, face: CubeFace) -> ([f32; 2], [f32; 2]) {
+        let axis = face.index() % 3;
+        let [a, b] = [(axis + 1) % 3, (axis + 2) % 3];
+        let (a, b) = (a.min(b), a.max(b));
+        ([self.min[a], self.min[b]], [self.max[a], self.max[b]])
+    }
+}
+
+/// Geometry of the textured block.
+///
+/// ```yaml
+/// shape: slab
+/// # or
+/// shape: !boxes
+///   - min: [0.0, 0.0, 0.0]
+///     max: [1.0, 0.25, 1.0]
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockShape {
+    #[default]
+    Cube,
+    /// Bottom half of the cube
+    Slab,
+    /// Bottom half with the upper step at -Z
+    Stairs,
+    /// Thin post in the middle; collider is higher than the block
+    Fence,
+    /// Thin wall across the X axis
+    Pane,
+    Boxes(Vec<ShapeBox>),
+}
+
+impl BlockShape {
+    pub fn is_cube(&self) -> bool {
+        *self == BlockShape::Cube
+    }
+
+    /// Boxes of the unrotated block
+    pub fn get_boxes(&self) -> Vec<ShapeBox> {
+        match self {
+            BlockShape::Cube => vec![ShapeBox::full()],
+            BlockShape::Slab => vec![ShapeBox::new([0.0; 3], [1.0, 0.5, 1.0])],
+            BlockShape::Stairs => vec![
+                ShapeBox::new([0.0; 3], [1.0, 0.5, 1.0]),
+                ShapeBox::new([0.0, 0.5, 0.0], [1.0, 1.0, 0.5]),
+            ],
+            BlockShape::Fence => vec![ShapeBox::new([0.375, 0.0, 0.375], [0.625, 1.0, 0.625])],
+            BlockShape::Pane => vec![ShapeBox::new([0.0, 0.0, 0.4375], [1.0, 1.0, 0.5625])],
+            BlockShape::Boxes(boxes) => boxes.clone(),
+        }
+    }
+
+    pub fn get_rotated_boxes(&self, rotation: &Option<BlockFace>) -> Vec<ShapeBox> {
+        self.get_boxes().iter().map(|b| b.rotate(rotation)).collect()
+    }
+
+    /// Collision boxes; may go outside of the block, like the fence which can't be jumped over
+    pub fn get_collider_boxes(&self, rotation: &Option<BlockFace>) -> Vec<ShapeBox> {
+        let mut boxes = self.get_rotated_boxes(rotation);
+        if *self == BlockShape::Fence {
+            for b in boxes.iter_mut() {
+                b.max[1] = 1.5;
+            }
+        }
+        boxes
+    }
+
+    /// Bit mask of the block sides completely covered by the shape, indexed by [`CubeFace::index`].
+    ///
+    /// Only covered sides hide the faces of the neighbours.
+    pub fn get_full_faces(&self, rotation: &Option<BlockFace>) -> u8 {
+        if self.is_cube() {
+            return FULL_FACES;
+        }
+        let boxes = self.get_rotated_boxes(rotation);
+        let mut mask = 0;
+        for face in CubeFace::ALL {
+            let boundary = match face.index() < 3 {
+                true => 0.0,
+                false => 1.0,
+            };
+            let rects: Vec<_> = boxes
+                .iter()
+                .filter(|b| b.get_face_depth(face) == boundary)
+                .map(|b| b.get_face_rect(face))
+                .collect();
+            if covers_unit_square(&rects) {
+                mask |= 1 << face.index();
+            }
+        }
+        mask
+    }
+}
+
+/// Checks that the union of the rects covers the whole 0..1 square
+fn covers_unit_square(rects: &[([f32; 2], [f32; 2])]) -> bool {
+    let mut edges: [Vec<f32>; 2] = [vec![0.0, 1.0], vec![0.0, 1.0]];
+    for (min, max) in rects.iter() {
+        for i in 0..2 {
+            edges[i].push(min[i].clamp(0.0, 1.0));
+            edges[i].push(max[i].clamp(0.0, 1.0));
+        }
+    }
+    for e in edges.iter_mut() {
+        e.sort_by(|a, b| a.total_cmp(b));
+        e.dedup();
+    }
+    // Center of every cell between the edges must be inside some rect
+    for a in edges[0].windows(2) {
+        for b in edges[1].windows(2) {
+            let p = [(a[0] + a[1]) / 2.0, (b[0] + b[1]) / 2.0];
+            let covered = rects
+                .iter()
+                .any(|(min, max)| min[0] <= p[0] && p[0] <= max[0] && min[1] <= p[1] && p[1] <= max[1]);
+            if !covered {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlockShape, ShapeBox, FULL_FACES};
+    use crate::blocks::{block_info::BlockFace, block_type::CubeFace};
+
+    fn mask(faces: &[CubeFace]) -> u8 {
+        faces.iter().fold(0, |m, f| m | 1 << f.index())
+    }
+
+    #[test]
+    fn test_shape_full_faces() {
+        assert_eq!(BlockShape::Cube.get_full_faces(&None), FULL_FACES);
+        assert_eq!(BlockShape::Slab.get_full_faces(&None), mask(&[CubeFace::NegY]));
+        assert_eq!(
+            BlockShape::Stairs.get_full_faces(&None),
+            mask(&[CubeFace::NegY, CubeFace::NegZ])
+        );
+        // Back of the stairs is turned from -Z to +X
+        assert_eq!(
+            BlockShape::Stairs.get_full_faces(&Some(BlockFace::West)),
+            mask(&[CubeFace::NegY, CubeFace::PosX])
+        );
+        assert_eq!(
+            BlockShape::Stairs.get_full_faces(&Some(BlockFace::East)),
+            mask(&[CubeFace::NegY, CubeFace::NegX])
+        );
+        assert_eq!(BlockShape::Fence.get_full_faces(&None), 0);
+
+        let halves = BlockShape::Boxes(vec![
+            ShapeBox::new([0.0; 3], [0.5, 1.0, 1.0]),
+            ShapeBox::new([0.5, 0.0, 0.0], [1.0, 1.0, 1.0]),
+        ]);
+        assert_eq!(halves.get_full_faces(&None), FULL_FACES);
+    }
+
+    #[test]
+    fn test_shape_yaml() {
+        let shape: BlockShape = serde_yaml::from_str("slab").unwrap();
+        assert_eq!(shape, BlockShape::Slab);
+        let shape: BlockShape = serde_yaml::from_str("!boxes\n- min: [0, 0, 0]\n  max: [1, 0.25, 1]").unwrap();
+        assert_eq!(shape.get_boxes()[0].max, [1.0, 0.25, 1.0]);
+        assert_eq!(BlockShape::Fence.get_collider_boxes(&None)[0].max[1], 1.5);
+    }
+}
@@ -1,6 +1,13 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use strum_macros::IntoStaticStr;
 
-use super::voxel_visibility::VoxelVisibility;
+use super::{
+    block_collider::BlockCollider,
+    block_info::BlockFace,
+    block_shape::{BlockShape, ShapeBox},
+    voxel_visibility::VoxelVisibility,
+};
 
 /// Defines a block type with its properties and behavior.
 ///
@@ -15,6 +22,8 @@ pub struct BlockType {
 
     collider_type: ColliderType,
 
+    collider: BlockCollider,
+
     category: String,
 
     map_color: Option<BlockColor>,
@@ -27,27 +36,31 @@ impl BlockType {
 }
 
 impl BlockType {
-    fn generate_slug(block_content: &BlockContent) -> String {
-        let path = match &block_content {
-            BlockContent::Texture { texture, .. } => texture.clone(),
-            BlockContent::ModelCube { model, .. } => model.clone(),
-        };
+    /// File name of the path without extension; `None` if it doesn't fit the slug format
+    pub fn slug_from_path(path: &str) -> Option<String> {
         let re = regex::Regex::new(REGEX_FILE_NAME).unwrap();
-        let Some(re) = re.captures(&path) else {
+        Some(re.captures(path)?.get(1)?.as_str().into())
+    }
+
+    fn generate_slug(block_content: &BlockContent) -> String {
+        let path = block_content.get_main_path();
+        let Some(slug) = BlockType::slug_from_path(path) else {
             panic!("Path \"{}\" regex return None", path);
         };
-        let Some(slug) = re.get(1) else {
-            panic!("Path \"{}\" regex group not found", path);
-        };
-        slug.as_str().into()
+        slug
     }
 
     pub fn new(block_content: BlockContent) -> Self {
         let slug = BlockType::generate_slug(&block_content);
+        Self::with_slug(slug, block_content)
+    }
+
+    fn with_slug(slug: String, block_content: BlockContent) -> Self {
         Self {
             slug: slug,
             block_content,
             collider_type: Default::default(),
+            collider: Default::default(),
             category: BlockType::default_category(),
             map_color: None,
         }
@@ -76,6 +89,15 @@ impl BlockType {
         &self.collider_type
     }
 
+    pub fn collider(mut self, collider: BlockCollider) -> Self {
+        self.collider = collider;
+        self
+    }
+
+    pub fn get_collider(&self) -> &BlockCollider {
+        &self.collider
+    }
+
     pub fn category(mut self, category: String) -> Self {
         self.category = category;
         self
@@ -97,6 +119,19 @@ impl BlockType {
         &mut self.block_content
     }
 
+    /// Boxes for the physics; sensors use them as trigger volumes
+    pub fn get_collider_boxes(&self, rotation: &Option<BlockFace>) -> Vec<ShapeBox> {
+        match self.collider.get_rotated_boxes(rotation) {
+            Some(boxes) => boxes,
+            None => self.block_content.get_shape().get_collider_boxes(rotation),
+        }
+    }
+
+    /// Mask for [`ChunkShapeInfo::full_faces`](crate::blocks::chunk_shape_info::ChunkShapeInfo::full_faces)
+    pub fn get_full_faces(&self, rotation: &Option<BlockFace>) -> u8 {
+        self.block_content.get_shape().get_full_faces(rotation)
+    }
+
     pub fn get_model(&self) -> Option<&String> {
         match &self.block_content {
             BlockContent::ModelCube { model, .. } => {
@@ -133,6 +168,110 @@ impl Default for ColliderType {
 
 pub type BlockColor = [u8; 3];
 
+/// Face of the cube; the order matches the faces of
+/// [`RIGHT_HANDED_Y_UP_CONFIG`](crate::utils::block_mesh::RIGHT_HANDED_Y_UP_CONFIG),
+/// so [`CubeFace::index`] is the quad group index of the mesher.
+///
+/// Unlike [`BlockFace`], which is the horizontal direction of a placed block,
+/// it covers all six sides; [`CubeFace::to_block_local`] links the two.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, IntoStaticStr)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum CubeFace {
+    NegX,
+    NegY,
+    NegZ,
+    PosX,
+    PosY,
+    PosZ,
+}
+
+impl CubeFace {
+    pub const ALL: [CubeFace; 6] = [
+        CubeFace::NegX,
+        CubeFace::NegY,
+        CubeFace::NegZ,
+        CubeFace::PosX,
+        CubeFace::PosY,
+        CubeFace::PosZ,
+    ];
+
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// Name of the face in yaml, like "pos_x"
+    pub fn as_str(&self) -> &'static str {
+        self.into()
+    }
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    pub fn is_side(&self) -> bool {
+        !matches!(self, CubeFace::NegY | CubeFace::PosY)
+    }
+
+    pub fn get_normal(&self) -> [i64; 3] {
+        let mut normal = [0; 3];
+        normal[self.index() % 3] = if self.index() < 3 { -1 } else { 1 };
+        normal
+    }
+
+    /// Face of the unrotated block which is seen from this side after the block
+    /// is rotated by the [`BlockFace::get_rotation`] yaw.
+    ///
+    /// The unrotated block faces `South` (+Z); every [`BlockFace::rotate_left`] turn moves
+    /// its +Z side to -X, the same way as the rotated structure templates.
+    pub fn to_block_local(&self, rotation: &Option<BlockFace>) -> CubeFace {
+        let turns = rotation.map_or(0, |r| r.get_turns());
+        let mut face = *self;
+        for _ in 0..turns {
+            face = match face {
+                CubeFace::NegX => CubeFace::PosZ,
+                CubeFace::PosZ => CubeFace::PosX,
+                CubeFace::PosX => CubeFace::NegZ,
+                CubeFace::NegZ => CubeFace::NegX,
+                _ => face,
+            };
+        }
+        face
+    }
+}
+
+/// Frames are shown one after another in a loop.
+///
+/// ```yaml
+/// animation:
+///   frames:
+///     - default://assets/block/water_0.png
+///     - default://assets/block/water_1.png
+///   frame_time: 0.1
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TextureAnimation {
+    pub frames: Vec<String>,
+
+    /// Seconds per frame
+    pub frame_time: f32,
+}
+
+impl TextureAnimation {
+    pub fn get_frame(&self, time: f32) -> Option<&String> {
+        let index = get_frame_index(self.frames.len(), self.frame_time, time);
+        self.frames.get(index)
+    }
+}
+
+/// Index of the looped animation frame shown at `time` in seconds
+pub(crate) fn get_frame_index(frames_count: usize, frame_time: f32, time: f32) -> usize {
+    if frames_count == 0 || frame_time <= 0.0 {
+        return 0;
+    }
+    (time / frame_time) as usize % frames_count
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum BlockContent {
@@ -148,6 +287,17 @@ pub enum BlockContent {
         // For texturing and collider building
         #[serde(default)]
         voxel_visibility: VoxelVisibility,
+
+        // Textures of the single faces of the unrotated block, override all others
+        #[serde(default)]
+        faces: BTreeMap<CubeFace, String>,
+
+        // Animation of the faces showing the main texture
+        #[serde(default)]
+        animation: Option<TextureAnimation>,
+
+        #[serde(default)]
+        shape: BlockShape,
     },
     ModelCube {
         model: String,
@@ -165,6 +315,102 @@ impl BlockContent {
         }
     }
 
+    /// Models don't take part in the voxel meshing
+    pub fn get_voxel_visibility(&self) -> VoxelVisibility {
+        match self {
+            BlockContent::Texture { voxel_visibility, .. } => *voxel_visibility,
+            BlockContent::ModelCube { .. } => VoxelVisibility::NonVoxel,
+        }
+    }
+
+    /// Texture of the cube face; `None` for models
+    pub fn get_face_texture(&self, face: CubeFace) -> Option<&String> {
+        let BlockContent::Texture {
+            texture,
+            side_texture,
+            bottom_texture,
+            faces,
+            ..
+        } = self
+        else {
+            return None;
+        };
+        if let Some(face_texture) = faces.get(&face) {
+            return Some(face_texture);
+        }
+        let face_texture = match face {
+            CubeFace::PosY => None,
+            CubeFace::NegY => bottom_texture.as_ref(),
+            _ => side_texture.as_ref(),
+        };
+        Some(face_texture.unwrap_or(texture))
+    }
+
+    /// Animation of the face; only faces showing the main texture are animated
+    pub fn get_face_animation(&self, face: CubeFace) -> Option<&TextureAnimation> {
+        let BlockContent::Texture { texture, animation, .. } = self else {
+            return None;
+        };
+        match self.get_face_texture(face) == Some(texture) {
+            true => animation.as_ref(),
+            false => None,
+        }
+    }
+
+    /// Texture or model path; the slug of the block without an explicit one is generated from it
+    pub fn get_main_path(&self) -> &String {
+        match self {
+            BlockContent::Texture { texture, .. } => texture,
+            BlockContent::ModelCube { model, .. } => model,
+        }
+    }
+
+    /// All resources of the content with the manifest field names
+    pub fn get_resource_paths(&self) -> Vec<(String, &String)> {
+        let mut paths = Vec::new();
+        match self {
+            BlockContent::Texture {
+                texture,
+                side_texture,
+                side_overlay,
+                bottom_texture,
+                faces,
+                animation,
+                ..
+            } => {
+                paths.push(("texture".to_string(), texture));
+                let optional = [
+                    ("side_texture", side_texture),
+                    ("side_overlay", side_overlay),
+                    ("bottom_texture", bottom_texture),
+                ];
+                for (field, path) in optional {
+                    if let Some(path) = path.as_ref() {
+                        paths.push((field.to_string(), path));
+                    }
+                }
+                for (face, path) in faces.iter() {
+                    paths.push((format!("faces.{}", face.as_str()), path));
+                }
+                if let Some(animation) = animation.as_ref() {
+                    for path in animation.frames.iter() {
+                        paths.push(("animation.frames".to_string(), path));
+                    }
+                }
+            }
+            BlockContent::ModelCube { model, .. } => paths.push(("model".to_string(), model)),
+        }
+        paths
+    }
+
+    /// Overlay drawn over the face texture, tinted by `colors_scheme`
+    pub fn get_face_overlay(&self, face: CubeFace) -> Option<&String> {
+        match self {
+            BlockContent::Texture { side_overlay, .. } if face.is_side() => side_overlay.as_ref(),
+            _ => None,
+        }
+    }
+
     pub fn single<S: Into<String>>(texture: S) -> BlockContent {
         BlockContent::Texture {
             texture: texture.into(),
@@ -173,9 +419,50 @@ impl BlockContent {
             bottom_texture: None,
             colors_scheme: None,
             voxel_visibility: VoxelVisibility::default(),
+            faces: Default::default(),
+            animation: None,
+            shape: Default::default(),
+        }
+    }
+
+    /// Overrides the texture of the single face; ignored for models
+    pub fn face_texture<S: Into<String>>(mut self, face: CubeFace, path: S) -> Self {
+        if let BlockContent::Texture { faces, .. } = &mut self {
+            faces.insert(face, path.into());
+        }
+        self
+    }
+
+    pub fn voxel_visibility(mut self, new_visibility: VoxelVisibility) -> Self {
+        if let BlockContent::Texture { voxel_visibility, .. } = &mut self {
+            *voxel_visibility = new_visibility;
+        }
+        self
+    }
+
+    pub fn shape(mut self, new_shape: BlockShape) -> Self {
+        if let BlockContent::Texture { shape, .. } = &mut self {
+            *shape = new_shape;
+        }
+        self
+    }
+
+    /// Models are treated as cubes
+    pub fn get_shape(&self) -> &BlockShape {
+        const CUBE: &BlockShape = &BlockShape::Cube;
+        match self {
+            BlockContent::Texture { shape, .. } => shape,
+            _ => CUBE,
         }
     }
 
+    pub fn animation(mut self, new_animation: TextureAnimation) -> Self {
+        if let BlockContent::Texture { animation, .. } = &mut self {
+            *animation = Some(new_animation);
+        }
+        self
+    }
+
     pub fn texture<S: Into<String>>(
         texture: S,
         side_texture: Option<S>,
@@ -198,6 +485,9 @@ impl BlockContent {
             },
             colors_scheme: None,
             voxel_visibility: VoxelVisibility::default(),
+            faces: Default::default(),
+            animation: None,
+            shape: Default::default(),
         }
     }
 }
@@ -212,6 +502,9 @@ pub struct BlockTypeManifest {
     #[serde(default)]
     collider_type: ColliderType,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collider: Option<BlockCollider>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub category: Option<String>,
 
@@ -220,18 +513,30 @@ pub struct BlockTypeManifest {
 }
 
 impl BlockTypeManifest {
-    pub fn to_block(&self) -> BlockType {
+    /// The slug is generated from the content path only if it's not set explicitly
+    pub fn to_block(&self) -> Result<BlockType, String> {
+        let slug = match self.slug.as_ref() {
+            Some(slug) => slug.clone(),
+            None => {
+                let path = self.block_content.get_main_path();
+                let Some(slug) = BlockType::slug_from_path(path) else {
+                    return Err(format!(
+                        "&cslug can't be generated from &4\"{}\"&c, set it explicitly",
+                        path
+                    ));
+                };
+                slug
+            }
+        };
         let category = match self.category.as_ref() {
             Some(c) => c.clone(),
             None => BlockType::default_category(),
         };
-        let mut b = BlockType::new(self.block_content.clone())
+        let b = BlockType::with_slug(slug, self.block_content.clone())
             .category(category)
             .collider_type(self.collider_type.clone())
+            .collider(self.collider.clone().unwrap_or_default())
             .map_color(self.map_color.clone());
-        if let Some(slug) = self.slug.as_ref() {
-            b = b.set_slug(slug.clone());
-        }
-        b
+        Ok(b)
     }
 }
@@ -0,0 +1,454 @@
+//! Checks of the block manifests before the block types are created.
+//!
+//! All problems are collected as [`BlockDiagnostic`] instead of stopping at the first one,
+//! so a broken manifest file can be fixed in one go.
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use crate::{chunks::chunk_data::BlockIndexType, default_blocks_ids::BlockID, resource_packs::uri::ResourceUri};
+
+use super::block_type::{BlockContent, BlockType, BlockTypeManifest};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockDiagnosticKind {
+    /// Yaml can't be parsed
+    Parse {
+        message: String,
+    },
+    /// Resource path is not a valid "namespace://path" uri
+    InvalidPath {
+        field: String,
+        path: String,
+    },
+    /// Slug can't be generated from the path of the block without an explicit slug
+    InvalidSlugPath {
+        path: String,
+    },
+    DuplicateSlug {
+        slug: String,
+    },
+    UnknownResource {
+        field: String,
+        path: String,
+    },
+    /// Block has `side_texture` and `colors_scheme`, but sides are tinted only through `side_overlay`
+    ColorsSchemeWithoutOverlay,
+    /// Animation without frames or with non positive frame time
+    InvalidAnimation,
+    /// Shape box is empty or goes outside of the block
+    InvalidShape,
+    /// See [`BlockCollider::is_valid`](super::block_collider::BlockCollider::is_valid)
+    InvalidCollider,
+    /// Stored id of the block is not equal to the hardcoded one
+    HardcodedIdConflict {
+        stored_id: BlockIndexType,
+        hardcoded_id: BlockIndexType,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockDiagnostic {
+    /// Index of the manifest in the list
+    index: Option<usize>,
+    /// Yaml line number starting from 1
+    line: Option<usize>,
+    slug: Option<String>,
+    kind: BlockDiagnosticKind,
+}
+
+impl BlockDiagnostic {
+    pub fn get_index(&self) -> Option<usize> {
+        self.index
+    }
+
+    pub fn get_line(&self) -> Option<usize> {
+        self.line
+    }
+
+    pub fn get_slug(&self) -> Option<&String> {
+        self.slug.as_ref()
+    }
+
+    pub fn get_kind(&self) -> &BlockDiagnosticKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for BlockDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "&cline {}: ", line)?;
+        }
+        if let Some(slug) = self.slug.as_ref() {
+            write!(f, "&cblock &4\"{}\" ", slug)?;
+        }
+        match &self.kind {
+            BlockDiagnosticKind::Parse { message } => write!(f, "&cyaml parsing error: &4{}", message),
+            BlockDiagnosticKind::InvalidPath { field, path } => {
+                write!(f, "&c{} &4\"{}\" &cis not a valid resource path", field, path)
+            }
+            BlockDiagnosticKind::InvalidSlugPath { path } => {
+                write!(f, "&cslug can't be generated from &4\"{}\"&c, set it explicitly", path)
+            }
+            BlockDiagnosticKind::DuplicateSlug { slug } => write!(f, "&cslug &4\"{}\" &cis duplicated", slug),
+            BlockDiagnosticKind::UnknownResource { field, path } => {
+                write!(f, "&c{} resource &4\"{}\" &cnot found", field, path)
+            }
+            BlockDiagnosticKind::ColorsSchemeWithoutOverlay => {
+                write!(f, "&ccolors_scheme is set with side_texture but without side_overlay")
+            }
+            BlockDiagnosticKind::InvalidAnimation => {
+                write!(f, "&canimation must have frames and positive frame_time")
+            }
+            BlockDiagnosticKind::InvalidShape => write!(f, "&cshape boxes must be inside the block and not empty"),
+            BlockDiagnosticKind::InvalidCollider => write!(f, "&ccollider boxes or properties are invalid"),
+            BlockDiagnosticKind::HardcodedIdConflict {
+                stored_id,
+                hardcoded_id,
+            } => write!(
+                f,
+                "&cstored id:&4{} &cis not equal to hardcoded id:&4{}",
+                stored_id, hardcoded_id
+            ),
+        }
+    }
+}
+
+/// Joins diagnostics into a single error message
+pub fn format_diagnostics(diagnostics: &[BlockDiagnostic]) -> String {
+    diagnostics.iter().map(|d| d.to_string()).collect::<Vec<_>>().join("\n")
+}
+
+/// Line numbers of the top level list items of the yaml
+struct YamlLines<'a> {
+    lines: Vec<&'a str>,
+    item_starts: Vec<usize>,
+}
+
+impl<'a> YamlLines<'a> {
+    fn parse(text: &'a str) -> Self {
+        let lines: Vec<&str> = text.lines().collect();
+        let item_starts = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| *l == &"-" || l.starts_with("- "))
+            .map(|(i, _)| i)
+            .collect();
+        Self { lines, item_starts }
+    }
+
+    /// Line of the key inside the item, or the item start line
+    fn find(&self, index: usize, key: &str) -> Option<usize> {
+        let start = *self.item_starts.get(index)?;
+        let end = self.item_starts.get(index + 1).copied().unwrap_or(self.lines.len());
+        let prefix = format!("{}:", key);
+        let found = (start..end).find(|i| {
+            let line = self.lines[*i].trim_start().trim_start_matches("- ");
+            line.starts_with(&prefix)
+        });
+        Some(found.unwrap_or(start) + 1)
+    }
+}
+
+/// Validates block manifests against the available resources and stored block ids.
+#[derive(Default)]
+pub struct BlockManifestValidator {
+    /// Resource check is skipped if not set
+    resources: Option<HashSet<String>>,
+    block_id_map: BTreeMap<BlockIndexType, String>,
+}
+
+impl BlockManifestValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Paths of all available resources, like "default://assets/block/dirt.png"
+    pub fn resources(mut self, resources: impl IntoIterator<Item = String>) -> Self {
+        self.resources = Some(resources.into_iter().collect());
+        self
+    }
+
+    /// Stored ids of the world, checked against hardcoded ids
+    pub fn block_id_map(mut self, block_id_map: BTreeMap<BlockIndexType, String>) -> Self {
+        self.block_id_map = block_id_map;
+        self
+    }
+
+    /// Parses the yaml list of manifests; diagnostics contain the yaml line numbers
+    pub fn validate_yaml(&self, text: &str) -> Result<Vec<BlockTypeManifest>, Vec<BlockDiagnostic>> {
+        let manifests: Vec<BlockTypeManifest> = match serde_yaml::from_str(text) {
+            Ok(m) => m,
+            Err(e) => {
+                return Err(vec![BlockDiagnostic {
+                    index: None,
+                    line: e.location().map(|l| l.line()),
+                    slug: None,
+                    kind: BlockDiagnosticKind::Parse { message: e.to_string() },
+                }])
+            }
+        };
+        let lines = YamlLines::parse(text);
+        let mut diagnostics = self.validate(&manifests);
+        for diagnostic in diagnostics.iter_mut() {
+            let Some(index) = diagnostic.index else {
+                continue;
+            };
+            let key = match &diagnostic.kind {
+                // Nested fields are looked up by the last key, like "pos_z" of "faces.pos_z"
+                BlockDiagnosticKind::InvalidPath { field, .. } | BlockDiagnosticKind::UnknownResource { field, .. } => {
+                    field.rsplit('.').next().unwrap()
+                }
+                BlockDiagnosticKind::InvalidAnimation => "animation",
+                BlockDiagnosticKind::InvalidShape => "shape",
+                BlockDiagnosticKind::InvalidCollider => "collider",
+                BlockDiagnosticKind::InvalidSlugPath { .. } => "block_content",
+                BlockDiagnosticKind::ColorsSchemeWithoutOverlay => "colors_scheme",
+                _ => "slug",
+            };
+            diagnostic.line = lines.find(index, key);
+        }
+        match diagnostics.is_empty() {
+            true => Ok(manifests),
+            false => Err(diagnostics),
+        }
+    }
+
+    pub fn validate(&self, manifests: &[BlockTypeManifest]) -> Vec<BlockDiagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut slugs: HashSet<String> = Default::default();
+        let stored_ids: HashMap<&String, BlockIndexType> = self.block_id_map.iter().map(|(id, s)| (s, *id)).collect();
+
+        for (index, manifest) in manifests.iter().enumerate() {
+            let mut push = |slug: Option<&String>, kind: BlockDiagnosticKind| {
+                diagnostics.push(BlockDiagnostic {
+                    index: Some(index),
+                    line: None,
+                    slug: slug.cloned(),
+                    kind,
+                });
+            };
+
+            let slug = match manifest.slug.as_ref() {
+                Some(slug) => Some(slug.clone()),
+                None => {
+                    let path = manifest.block_content.get_main_path();
+                    let slug = BlockType::slug_from_path(path);
+                    if slug.is_none() {
+                        push(None, BlockDiagnosticKind::InvalidSlugPath { path: path.clone() });
+                    }
+                    slug
+                }
+            };
+
+            for (field, path) in manifest.block_content.get_resource_paths() {
+                let kind = if path.parse::<ResourceUri>().is_err() {
+                    BlockDiagnosticKind::InvalidPath {
+                        field,
+                        path: path.clone(),
+                    }
+                } else if self.resources.as_ref().is_some_and(|r| !r.contains(path)) {
+                    BlockDiagnosticKind::UnknownResource {
+                        field,
+                        path: path.clone(),
+                    }
+                } else {
+                    continue;
+                };
+                push(slug.as_ref(), kind);
+            }
+
+            if let BlockContent::Texture {
+                side_texture: Some(_),
+                colors_scheme: Some(_),
+                side_overlay: None,
+                ..
+            } = &manifest.block_content
+            {
+                push(slug.as_ref(), BlockDiagnosticKind::ColorsSchemeWithoutOverlay);
+            }
+
+            if let BlockContent::Texture {
+                animation: Some(animation),
+                ..
+            } = &manifest.block_content
+            {
+                if animation.frames.is_empty() || animation.frame_time <= 0.0 {
+                    push(slug.as_ref(), BlockDiagnosticKind::InvalidAnimation);
+                }
+            }
+
+            let boxes = manifest.block_content.get_shape().get_boxes();
+            if boxes.is_empty() || boxes.iter().any(|b| !b.is_valid()) {
+                push(slug.as_ref(), BlockDiagnosticKind::InvalidShape);
+            }
+
+            if manifest.collider.as_ref().is_some_and(|c| !c.is_valid()) {
+                push(slug.as_ref(), BlockDiagnosticKind::InvalidCollider);
+            }
+
+            let Some(slug) = slug else {
+                continue;
+            };
+            if !slugs.insert(slug.clone()) {
+                push(Some(&slug), BlockDiagnosticKind::DuplicateSlug { slug: slug.clone() });
+            }
+            if let (Some(id), Some(stored_id)) = (BlockID::from_string(&slug), stored_ids.get(&slug)) {
+                if id.id() != *stored_id {
+                    push(
+                        Some(&slug),
+                        BlockDiagnosticKind::HardcodedIdConflict {
+                            stored_id: *stored_id,
+                            hardcoded_id: id.id(),
+                        },
+                    );
+                }
+            }
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{BlockDiagnosticKind, BlockManifestValidator};
+
+    const YAML: &str = "\
+- slug: grass
+  block_content: !texture
+    texture: default://assets/block/grass.png
+    side_texture: default://assets/block/dirt.png
+    colors_scheme:
+      - [1, 2, 3]
+
+- block_content: !texture
+    texture: default://assets/block/dirt.png
+    bottom_texture: assets/block/dirt.png
+
+- block_content: !texture
+    texture: default://assets/block/stone.png
+- slug: stone
+  block_content: !texture
+    texture: default://assets/block/missing.png
+";
+
+    #[test]
+    fn test_validate_yaml() {
+        let mut block_id_map = BTreeMap::new();
+        block_id_map.insert(100, "grass".to_string());
+        let validator = BlockManifestValidator::new()
+            .resources(
+                ["grass", "dirt", "stone"]
+                    .iter()
+                    .map(|s| format!("default://assets/block/{}.png", s)),
+            )
+            .block_id_map(block_id_map);
+
+        let diagnostics = validator.validate_yaml(YAML).unwrap_err();
+        let kinds: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.get_line(), d.get_kind().clone()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (Some(5), BlockDiagnosticKind::ColorsSchemeWithoutOverlay),
+                (
+                    Some(1),
+                    BlockDiagnosticKind::HardcodedIdConflict {
+                        stored_id: 100,
+                        hardcoded_id: 1
+                    }
+                ),
+                (
+                    Some(10),
+                    BlockDiagnosticKind::InvalidPath {
+                        field: "bottom_texture".to_string(),
+                        path: "assets/block/dirt.png".to_string()
+                    }
+                ),
+                (
+                    Some(16),
+                    BlockDiagnosticKind::UnknownResource {
+                        field: "texture".to_string(),
+                        path: "default://assets/block/missing.png".to_string()
+                    }
+                ),
+                (
+                    Some(14),
+                    BlockDiagnosticKind::DuplicateSlug {
+                        slug: "stone".to_string()
+                    }
+                ),
+            ]
+        );
+
+        let error = validator.validate_yaml("- slug: [").unwrap_err();
+        assert_eq!(error[0].get_line(), Some(1));
+    }
+
+    #[test]
+    fn test_validate_faces_and_animation() {
+        let yaml = "\
+- slug: furnace
+  block_content: !texture
+    texture: default://assets/block/furnace_side.png
+    faces:
+      pos_z: assets/block/furnace_front.png
+    animation:
+      frames: []
+      frame_time: 0.1
+";
+        let diagnostics = BlockManifestValidator::new().validate_yaml(yaml).unwrap_err();
+        let kinds: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.get_line(), d.get_kind().clone()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (
+                    Some(5),
+                    BlockDiagnosticKind::InvalidPath {
+                        field: "faces.pos_z".to_string(),
+                        path: "assets/block/furnace_front.png".to_string()
+                    }
+                ),
+                (Some(6), BlockDiagnosticKind::InvalidAnimation),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_collider() {
+        let yaml = |collider: &str| {
+            format!(
+                "- slug: lava\n  block_content: !texture\n    texture: default://assets/block/lava.png\n  collider:\n    {}\n",
+                collider
+            )
+        };
+        let validator = BlockManifestValidator::new();
+        assert!(validator.validate_yaml(&yaml("damage: {amount: 2.0}")).is_ok());
+        assert!(validator.validate_yaml(&yaml("liquid: {drag: 0.5}")).is_ok());
+
+        for invalid in [
+            "damage: {amount: 2.0, interval: 0.0}",
+            "damage: {amount: -1.0}",
+            "liquid: {buoyancy: -1.0}",
+            "liquid: {drag: .nan}",
+            "friction: -0.5",
+            "bounciness: 2.0",
+        ] {
+            let diagnostics = validator.validate_yaml(&yaml(invalid)).unwrap_err();
+            assert_eq!(
+                diagnostics[0].get_kind(),
+                &BlockDiagnosticKind::InvalidCollider,
+                "{}",
+                invalid
+            );
+            assert_eq!(diagnostics[0].get_line(), Some(4));
+        }
+    }
+}
@@ -1,10 +1,14 @@
-use super::voxel_visibility::{Voxel, VoxelVisibility};
+use super::{
+    block_shape::FULL_FACES,
+    voxel_visibility::{Voxel, VoxelVisibility},
+};
 use crate::{chunks::chunk_data::BlockDataInfo, utils::block_mesh::greedy::MergeVoxel};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ChunkShapeInfo {
     voxel_visibility: VoxelVisibility,
     block_info: Option<BlockDataInfo>,
+    full_faces: u8,
 }
 
 impl ChunkShapeInfo {
@@ -12,8 +16,15 @@ impl ChunkShapeInfo {
         Self {
             voxel_visibility,
             block_info,
+            full_faces: FULL_FACES,
         }
     }
+
+    /// Mask of [`BlockType::get_full_faces`](crate::blocks::block_type::BlockType::get_full_faces) for partial shapes
+    pub fn full_faces(mut self, full_faces: u8) -> Self {
+        self.full_faces = full_faces;
+        self
+    }
 }
 
 impl ChunkShapeInfo {
@@ -43,4 +54,8 @@ impl Voxel for ChunkShapeInfo {
     fn get_block_info(&self) -> &Option<BlockDataInfo> {
         &self.block_info
     }
+
+    fn get_full_faces(&self) -> u8 {
+        self.full_faces
+    }
 }
@@ -1,6 +1,10 @@
+pub mod block_collider;
 pub mod block_info;
+pub mod block_shape;
 pub mod voxel_visibility;
 pub mod block_type;
+pub mod block_validator;
+pub mod texture_atlas;
 
 #[cfg(feature = "full")]
 pub mod chunk_shape_info;
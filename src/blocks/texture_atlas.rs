@@ -0,0 +1,405 @@
+//! Packing of the block textures into atlas pages.
+//!
+//! Layout depends only on the texture paths and sizes, so the server and
+//! every client build the same atlas from the same block types.
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::{
+    block_info::BlockFace,
+    block_type::{get_frame_index, BlockType, CubeFace},
+};
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+/// Reads the image size from the PNG header without decoding it
+pub fn get_png_size(data: &[u8]) -> Result<[u32; 2], String> {
+    if data.len() < 24 || data[..8] != PNG_SIGNATURE || &data[12..16] != b"IHDR" {
+        return Err("&cimage is not a valid png".to_string());
+    }
+    let width = u32::from_be_bytes(data[16..20].try_into().unwrap());
+    let height = u32::from_be_bytes(data[20..24].try_into().unwrap());
+    Ok([width, height])
+}
+
+/// Texture position inside the atlas in pixels
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AtlasRegion {
+    page: u32,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+impl AtlasRegion {
+    pub fn get_page(&self) -> u32 {
+        self.page
+    }
+
+    pub fn get_position(&self) -> [u32; 2] {
+        [self.x, self.y]
+    }
+
+    pub fn get_size(&self) -> [u32; 2] {
+        [self.width, self.height]
+    }
+}
+
+/// Normalized texture rect inside the atlas page; (0, 0) is the top left corner
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct UvRect {
+    pub page: u32,
+    pub min: [f32; 2],
+    pub max: [f32; 2],
+}
+
+impl UvRect {
+    /// Maps the unit quad coordinates of
+    /// [`OrientedBlockFace::tex_coords`](crate::utils::block_mesh::OrientedBlockFace::tex_coords)
+    /// into the rect.
+    pub fn map_coords(&self, coords: [[f32; 2]; 4]) -> [[f32; 2]; 4] {
+        coords.map(|[u, v]| {
+            [
+                self.min[0] + (self.max[0] - self.min[0]) * u,
+                self.min[1] + (self.max[1] - self.min[1]) * v,
+            ]
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AnimatedUv {
+    pub frames: Vec<UvRect>,
+    pub frame_time: f32,
+}
+
+impl AnimatedUv {
+    pub fn get_frame(&self, time: f32) -> Option<&UvRect> {
+        let index = get_frame_index(self.frames.len(), self.frame_time, time);
+        self.frames.get(index)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FaceUv {
+    pub texture: UvRect,
+    pub overlay: Option<UvRect>,
+    /// Frames replacing the texture
+    pub animation: Option<AnimatedUv>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AtlasPage {
+    width: u32,
+    height: u32,
+    /// Texture paths with their regions, in placement order
+    textures: Vec<(String, AtlasRegion)>,
+}
+
+impl AtlasPage {
+    pub fn get_size(&self) -> [u32; 2] {
+        [self.width, self.height]
+    }
+
+    pub fn get_textures(&self) -> &Vec<(String, AtlasRegion)> {
+        &self.textures
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct TextureAtlas {
+    pages: Vec<AtlasPage>,
+    regions: BTreeMap<String, AtlasRegion>,
+    /// UVs of the block faces by the block slug, indexed by [`CubeFace::index`]
+    blocks: BTreeMap<String, [FaceUv; 6]>,
+}
+
+impl TextureAtlas {
+    pub fn get_pages(&self) -> &Vec<AtlasPage> {
+        &self.pages
+    }
+
+    pub fn get_region(&self, path: &str) -> Option<&AtlasRegion> {
+        self.regions.get(path)
+    }
+
+    pub fn get_uv(&self, path: &str) -> Option<UvRect> {
+        let region = self.regions.get(path)?;
+        let page = &self.pages[region.page as usize];
+        let (w, h) = (page.width as f32, page.height as f32);
+        Some(UvRect {
+            page: region.page,
+            min: [region.x as f32 / w, region.y as f32 / h],
+            max: [
+                (region.x + region.width) as f32 / w,
+                (region.y + region.height) as f32 / h,
+            ],
+        })
+    }
+
+    /// All six faces of the block; `None` for models and unknown blocks
+    pub fn get_block_faces(&self, block_slug: &str) -> Option<&[FaceUv; 6]> {
+        self.blocks.get(block_slug)
+    }
+
+    pub fn get_face(&self, block_slug: &str, face: CubeFace) -> Option<&FaceUv> {
+        Some(&self.blocks.get(block_slug)?[face.index()])
+    }
+
+    /// UVs for the world side of the rotated block, see [`BlockDataInfo::get_face`](crate::chunks::chunk_data::BlockDataInfo::get_face)
+    pub fn get_rotated_face(&self, block_slug: &str, face: CubeFace, rotation: &Option<BlockFace>) -> Option<&FaceUv> {
+        self.get_face(block_slug, face.to_block_local(rotation))
+    }
+}
+
+/// Shelf packer of the block textures.
+pub struct TextureAtlasBuilder {
+    page_size: u32,
+    padding: u32,
+}
+
+impl Default for TextureAtlasBuilder {
+    fn default() -> Self {
+        Self {
+            page_size: 1024,
+            padding: 0,
+        }
+    }
+}
+
+impl TextureAtlasBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Width and height of each page
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Empty pixels around each texture against bleeding on mipmaps
+    pub fn padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// `get_size` returns the size of the texture by its resource path,
+    /// for example with [`get_png_size`] over the resource pack data.
+    pub fn build<'a>(
+        &self,
+        block_types: impl Iterator<Item = &'a BlockType>,
+        mut get_size: impl FnMut(&str) -> Result<[u32; 2], String>,
+    ) -> Result<TextureAtlas, String> {
+        let block_types: Vec<&BlockType> = block_types.collect();
+
+        let mut sizes: BTreeMap<String, [u32; 2]> = Default::default();
+        for block_type in block_types.iter() {
+            let content = block_type.get_block_content();
+            for face in CubeFace::ALL {
+                let frames = content
+                    .get_face_animation(face)
+                    .map(|a| &a.frames[..])
+                    .unwrap_or_default();
+                let paths = [content.get_face_texture(face), content.get_face_overlay(face)];
+                for path in paths.into_iter().flatten().chain(frames.iter()) {
+                    if !sizes.contains_key(path) {
+                        sizes.insert(path.clone(), get_size(path)?);
+                    }
+                }
+            }
+        }
+
+        let mut atlas = TextureAtlas::default();
+        self.pack(&mut atlas, sizes)?;
+
+        for block_type in block_types.iter() {
+            let content = block_type.get_block_content();
+            let faces: Option<Vec<FaceUv>> = CubeFace::ALL
+                .iter()
+                .map(|face| {
+                    let animation = content.get_face_animation(*face).map(|a| AnimatedUv {
+                        frames: a.frames.iter().filter_map(|p| atlas.get_uv(p)).collect(),
+                        frame_time: a.frame_time,
+                    });
+                    Some(FaceUv {
+                        texture: atlas.get_uv(content.get_face_texture(*face)?)?,
+                        overlay: content.get_face_overlay(*face).and_then(|p| atlas.get_uv(p)),
+                        animation,
+                    })
+                })
+                .collect();
+            if let Some(faces) = faces {
+                atlas
+                    .blocks
+                    .insert(block_type.get_slug().clone(), faces.try_into().unwrap());
+            }
+        }
+        Ok(atlas)
+    }
+
+    fn pack(&self, atlas: &mut TextureAtlas, sizes: BTreeMap<String, [u32; 2]>) -> Result<(), String> {
+        // Tallest first, ties are broken by the path for the stable layout
+        let mut textures: Vec<(String, [u32; 2])> = sizes.into_iter().collect();
+        textures.sort_by(|(a_path, a), (b_path, b)| b[1].cmp(&a[1]).then(b[0].cmp(&a[0])).then(a_path.cmp(b_path)));
+
+        let (mut x, mut y, mut row_height) = (0, 0, 0);
+        for (path, [width, height]) in textures {
+            // Sizes come from the png headers, so they can be anything
+            let padded = |size: u32| self.padding.checked_mul(2).and_then(|p| size.checked_add(p));
+            let (w, h) = match (padded(width), padded(height)) {
+                (Some(w), Some(h)) if w <= self.page_size && h <= self.page_size => (w, h),
+                _ => {
+                    return Err(format!(
+                        "&ctexture &4\"{}\" &c{}x{} doesn't fit into the atlas page {}",
+                        path, width, height, self.page_size
+                    ));
+                }
+            };
+            // x and y never exceed the page size
+            if w > self.page_size - x {
+                x = 0;
+                y += row_height;
+                row_height = 0;
+            }
+            if atlas.pages.is_empty() || h > self.page_size - y {
+                atlas.pages.push(AtlasPage {
+                    width: self.page_size,
+                    height: self.page_size,
+                    textures: Default::default(),
+                });
+                x = 0;
+                y = 0;
+                row_height = 0;
+            }
+            let region = AtlasRegion {
+                page: atlas.pages.len() as u32 - 1,
+                x: x + self.padding,
+                y: y + self.padding,
+                width,
+                height,
+            };
+            atlas
+                .pages
+                .last_mut()
+                .unwrap()
+                .textures
+                .push((path.clone(), region.clone()));
+            atlas.regions.insert(path, region);
+            x += w;
+            row_height = row_height.max(h);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_png_size, TextureAtlasBuilder};
+    use crate::blocks::{
+        block_info::BlockFace,
+        block_type::{BlockContent, BlockType, CubeFace, TextureAnimation},
+    };
+
+    fn get_size(path: &str) -> Result<[u32; 2], String> {
+        match path.contains("big") {
+            true => Ok([32, 32]),
+            false => Ok([16, 16]),
+        }
+    }
+
+    #[test]
+    fn test_atlas_build() {
+        let blocks = vec![
+            BlockType::new(BlockContent::single("default://grass.png")),
+            BlockType::new(BlockContent::texture(
+                "default://log_top.png",
+                Some("default://big_log.png"),
+                None,
+                None,
+            )),
+            BlockType::new(BlockContent::texture(
+                "default://grass.png",
+                Some("default://dirt.png"),
+                Some("default://overlay.png"),
+                Some("default://dirt.png"),
+            ))
+            .set_slug("grass_block"),
+        ];
+        let builder = TextureAtlasBuilder::new().page_size(40);
+        let atlas = builder.build(blocks.iter(), get_size).unwrap();
+
+        // Same layout for any order of the blocks
+        let reversed = builder.build(blocks.iter().rev(), get_size).unwrap();
+        assert_eq!(atlas, reversed);
+
+        assert_eq!(atlas.get_pages().len(), 2);
+        assert_eq!(
+            atlas.get_region("default://big_log.png").unwrap().get_position(),
+            [0, 0]
+        );
+
+        let log = atlas.get_block_faces("log_top").unwrap();
+        assert_eq!(log[CubeFace::PosX.index()].texture.max, [32.0 / 40.0, 32.0 / 40.0]);
+        assert_ne!(log[CubeFace::PosY.index()].texture, log[CubeFace::PosX.index()].texture);
+
+        let grass = atlas.get_face("grass_block", CubeFace::NegZ).unwrap();
+        assert_eq!(Some(grass.texture), atlas.get_uv("default://dirt.png"));
+        assert_eq!(grass.overlay, atlas.get_uv("default://overlay.png"));
+        assert!(atlas.get_face("grass_block", CubeFace::PosY).unwrap().overlay.is_none());
+
+        assert!(grass.animation.is_none());
+
+        assert!(TextureAtlasBuilder::new()
+            .page_size(16)
+            .build(blocks.iter(), get_size)
+            .is_err());
+
+        // Broken png headers must not overflow
+        let huge = |_: &str| Ok([u32::MAX, 16]);
+        let builder = TextureAtlasBuilder::new().page_size(u32::MAX).padding(1);
+        assert!(builder.build(blocks.iter(), huge).is_err());
+    }
+
+    #[test]
+    fn test_atlas_faces() {
+        let furnace = BlockType::new(
+            BlockContent::single("default://furnace_side.png")
+                .face_texture(CubeFace::PosZ, "default://furnace_front.png")
+                .animation(TextureAnimation {
+                    frames: vec!["default://fire_0.png".to_string(), "default://fire_1.png".to_string()],
+                    frame_time: 0.5,
+                }),
+        );
+        let atlas = TextureAtlasBuilder::new().build([furnace].iter(), get_size).unwrap();
+
+        let front = atlas.get_uv("default://furnace_front.png");
+        let face = atlas.get_face("furnace_side", CubeFace::PosZ).unwrap();
+        assert_eq!(Some(face.texture), front);
+        assert!(face.animation.is_none());
+
+        // West rotation yaw of 90° turns the +Z front to -X
+        let west = Some(BlockFace::West);
+        let face = atlas.get_rotated_face("furnace_side", CubeFace::NegX, &west).unwrap();
+        assert_eq!(Some(face.texture), front);
+        let east = Some(BlockFace::East);
+        let face = atlas.get_rotated_face("furnace_side", CubeFace::PosX, &east).unwrap();
+        assert_eq!(Some(face.texture), front);
+
+        let side = atlas.get_face("furnace_side", CubeFace::PosY).unwrap();
+        let animation = side.animation.as_ref().unwrap();
+        assert_eq!(animation.get_frame(0.7).copied(), atlas.get_uv("default://fire_1.png"));
+        assert_eq!(animation.get_frame(1.2).copied(), atlas.get_uv("default://fire_0.png"));
+    }
+
+    #[test]
+    fn test_png_size() {
+        let mut data = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, 0, 0, 0, 13];
+        data.extend_from_slice(b"IHDR");
+        data.extend_from_slice(&16_u32.to_be_bytes());
+        data.extend_from_slice(&32_u32.to_be_bytes());
+        assert_eq!(get_png_size(&data).unwrap(), [16, 32]);
+        assert!(get_png_size(&data[..20]).is_err());
+    }
+}
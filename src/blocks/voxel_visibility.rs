@@ -1,4 +1,4 @@
-use crate::chunks::chunk_data::BlockDataInfo;
+use crate::{blocks::block_shape::FULL_FACES, chunks::chunk_data::BlockDataInfo};
 use serde::{Deserialize, Serialize};
 use strum_macros::Display;
 
@@ -29,6 +29,35 @@ impl Default for VoxelVisibility {
 pub trait Voxel {
     fn get_visibility(&self) -> VoxelVisibility;
     fn get_block_info(&self) -> &Option<BlockDataInfo>;
+
+    /// Sides completely covered by the voxel, see
+    /// [`BlockShape::get_full_faces`](crate::blocks::block_shape::BlockShape::get_full_faces).
+    ///
+    /// Voxels with partial shapes are not meshed as cubes, use [`shape_block_quads`](crate::utils::block_mesh::shape_block_quads).
+    fn get_full_faces(&self) -> u8 {
+        FULL_FACES
+    }
+}
+
+/// Checks if the face of the voxel must be meshed against its neighbour.
+///
+/// `face_index` is the [`CubeFace`](crate::blocks::block_type::CubeFace) index of the voxel face,
+/// see [`OrientedBlockFace::get_cube_face`](crate::utils::block_mesh::OrientedBlockFace::get_cube_face).
+pub fn is_face_visible<T: Voxel, N: Voxel>(voxel: &T, neighbour: &N, face_index: usize) -> bool {
+    if voxel.get_full_faces() != FULL_FACES {
+        return false;
+    }
+    // TODO: If the face lies between two transparent voxels, we choose not to mesh it. We might need to extend the
+    // IsOpaque trait with different levels of transparency to support this.
+    let opposite = (face_index + 3) % 6;
+    match neighbour.get_visibility() {
+        VoxelVisibility::Empty => true,
+        VoxelVisibility::Translucent => {
+            voxel.get_visibility() == VoxelVisibility::Opaque || neighbour.get_full_faces() & (1 << opposite) == 0
+        }
+        VoxelVisibility::Opaque => neighbour.get_full_faces() & (1 << opposite) == 0,
+        VoxelVisibility::NonVoxel => true,
+    }
 }
 
 /// Used as a dummy for functions that must wrap a voxel
@@ -43,6 +72,9 @@ impl<'a, T: Voxel> Voxel for IdentityVoxel<'a, T> {
     fn get_block_info(&self) -> &Option<BlockDataInfo> {
         self.0.get_block_info()
     }
+    fn get_full_faces(&self) -> u8 {
+        self.0.get_full_faces()
+    }
 }
 
 impl<'a, T: Voxel> From<&'a T> for IdentityVoxel<'a, T> {
@@ -68,6 +68,22 @@ impl BlockPosition {
         }
     }
 
+    pub fn get_x(&self) -> i64 {
+        self.x
+    }
+
+    pub fn get_y(&self) -> i64 {
+        self.y
+    }
+
+    pub fn get_z(&self) -> i64 {
+        self.z
+    }
+
+    pub fn offset(&self, x: i64, y: i64, z: i64) -> Self {
+        Self::new(self.x + x, self.y + y, self.z + z)
+    }
+
     pub fn get_position(&self) -> Vector3 {
         Vector3::new(self.x as f32, self.y as f32, self.z as f32)
     }
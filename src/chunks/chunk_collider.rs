@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{
+    blocks::{
+        block_collider::BlockCollider,
+        block_shape::ShapeBox,
+        block_type::{BlockType, ColliderType},
+    },
+    utils::compressable::Compressable,
+    CHUNK_SIZE,
+};
+
+use super::{
+    block_position::ChunkBlockPosition,
+    chunk_data::{BlockIndexType, ChunkData},
+};
+
+/// Boxes of all blocks with the same id, in the chunk local coordinates
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChunkColliderGroup {
+    block_id: BlockIndexType,
+    collider_type: ColliderType,
+    collider: BlockCollider,
+    boxes: Vec<ShapeBox>,
+}
+
+impl ChunkColliderGroup {
+    pub fn get_block_id(&self) -> BlockIndexType {
+        self.block_id
+    }
+
+    pub fn get_collider_type(&self) -> &ColliderType {
+        &self.collider_type
+    }
+
+    pub fn get_collider(&self) -> &BlockCollider {
+        &self.collider
+    }
+
+    pub fn get_boxes(&self) -> &Vec<ShapeBox> {
+        &self.boxes
+    }
+}
+
+/// Collision description of the chunk, built the same way on the server and on the client.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ChunkCollider {
+    /// Sorted by the block id
+    groups: Vec<ChunkColliderGroup>,
+}
+
+impl Compressable for ChunkCollider {}
+
+impl ChunkCollider {
+    /// Full cube boxes of the same block standing in a row along X are merged into one box.
+    pub fn build<'a>(
+        chunk_data: &ChunkData,
+        get_block_type: impl Fn(BlockIndexType) -> Option<&'a BlockType>,
+    ) -> Result<Self, String> {
+        let mut groups: BTreeMap<BlockIndexType, ChunkColliderGroup> = Default::default();
+        for section_index in 0..chunk_data.len() {
+            let section = chunk_data.get(section_index).unwrap();
+            let section_y = (section_index * CHUNK_SIZE as usize) as f32;
+
+            for (index, block_info) in section.iter() {
+                let block_id = block_info.get_id();
+                let Some(block_type) = get_block_type(block_id) else {
+                    return Err(format!("&cblock id &4{} &cnot found", block_id));
+                };
+                let group = groups.entry(block_id).or_insert_with(|| ChunkColliderGroup {
+                    block_id,
+                    collider_type: block_type.get_collider_type().clone(),
+                    collider: block_type.get_collider().clone(),
+                    boxes: Default::default(),
+                });
+
+                let pos = ChunkBlockPosition::delinearize(index as u16);
+                let offset = [pos.x as f32, section_y + pos.y as f32, pos.z as f32];
+                for block_box in block_type.get_collider_boxes(block_info.get_face()) {
+                    let new_box = ShapeBox::new(
+                        [0, 1, 2].map(|i| block_box.min[i] + offset[i]),
+                        [0, 1, 2].map(|i| block_box.max[i] + offset[i]),
+                    );
+                    // Blocks are iterated with X changing first, so the row neighbour is the last box;
+                    // all boxes of the group have the same shape
+                    let is_full = block_box == ShapeBox::full();
+                    match group.boxes.last_mut() {
+                        Some(last)
+                            if is_full
+                                && last.max[0] == new_box.min[0]
+                                && last.min[1..] == new_box.min[1..]
+                                && last.max[1..] == new_box.max[1..] =>
+                        {
+                            last.max[0] = new_box.max[0];
+                        }
+                        _ => group.boxes.push(new_box),
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            groups: groups.into_values().collect(),
+        })
+    }
+
+    pub fn get_groups(&self) -> &Vec<ChunkColliderGroup> {
+        &self.groups
+    }
+
+    pub fn get_group(&self, block_id: BlockIndexType) -> Option<&ChunkColliderGroup> {
+        self.groups.iter().find(|g| g.block_id == block_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChunkCollider;
+    use crate::{
+        blocks::{
+            block_collider::{BlockCollider, LiquidProperties},
+            block_shape::BlockShape,
+            block_type::{BlockContent, BlockType, ColliderType},
+        },
+        chunks::{
+            block_position::ChunkBlockPosition,
+            chunk_data::{BlockDataInfo, ChunkData, ChunkSectionData},
+        },
+    };
+
+    #[test]
+    fn test_chunk_collider_build() {
+        let stone = BlockType::new(BlockContent::single("default://stone.png"));
+        let slab = BlockType::new(BlockContent::single("default://slab.png").shape(BlockShape::Slab));
+        let water = BlockType::new(BlockContent::single("default://water.png"))
+            .collider_type(ColliderType::Sensor)
+            .collider(BlockCollider {
+                liquid: Some(LiquidProperties {
+                    buoyancy: 1.0,
+                    drag: 0.5,
+                }),
+                ..Default::default()
+            });
+        let block_types = [stone, slab, water];
+
+        let mut section = ChunkSectionData::default();
+        for x in 0..3 {
+            section.insert(&ChunkBlockPosition::new(x, 0, 0), BlockDataInfo::create(0));
+        }
+        section.insert(&ChunkBlockPosition::new(5, 0, 0), BlockDataInfo::create(0));
+        section.insert(&ChunkBlockPosition::new(0, 1, 0), BlockDataInfo::create(1));
+        section.insert(&ChunkBlockPosition::new(1, 1, 0), BlockDataInfo::create(1));
+        section.insert(&ChunkBlockPosition::new(0, 2, 0), BlockDataInfo::create(2));
+        let mut chunk_data = ChunkData::default();
+        chunk_data.push_section(ChunkSectionData::default());
+        chunk_data.push_section(section);
+
+        let collider = ChunkCollider::build(&chunk_data, |id| block_types.get(id as usize)).unwrap();
+        assert_eq!(collider.get_groups().len(), 3);
+
+        let stone = collider.get_group(0).unwrap().get_boxes();
+        assert_eq!(stone.len(), 2);
+        assert_eq!((stone[0].min, stone[0].max), ([0.0, 16.0, 0.0], [3.0, 17.0, 1.0]));
+
+        // Slabs are not merged
+        let slab = collider.get_group(1).unwrap().get_boxes();
+        assert_eq!(slab.len(), 2);
+        assert_eq!(slab[0].max, [1.0, 17.5, 1.0]);
+
+        let water = collider.get_group(2).unwrap();
+        assert!(water.get_collider_type().is_sensor());
+        assert!(water.get_collider().liquid.is_some());
+
+        chunk_data.change_block(1, &ChunkBlockPosition::new(9, 9, 9), Some(BlockDataInfo::create(7)));
+        assert!(ChunkCollider::build(&chunk_data, |id| block_types.get(id as usize)).is_err());
+    }
+}
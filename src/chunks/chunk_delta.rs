@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+use crate::CHUNK_SIZE;
+
+use super::{
+    block_position::{BlockPosition, BlockPositionTrait},
+    chunk_data::{BlockDataInfo, ChunkData},
+    chunk_position::ChunkPosition,
+};
+
+/// Single block change with the global position; `None` block removes the block
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct BlockChange {
+    position: BlockPosition,
+    block: Option<BlockDataInfo>,
+}
+
+impl BlockChange {
+    pub fn create(position: BlockPosition, block: Option<BlockDataInfo>) -> Self {
+        Self { position, block }
+    }
+
+    pub fn get_position(&self) -> &BlockPosition {
+        &self.position
+    }
+
+    pub fn get_block(&self) -> &Option<BlockDataInfo> {
+        &self.block
+    }
+}
+
+/// List of block changes, a lightweight alternative to sending the whole chunk back.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ChunkDelta {
+    changes: Vec<BlockChange>,
+}
+
+impl ChunkDelta {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Later changes of the same position override earlier ones
+    pub fn set(&mut self, position: BlockPosition, block: Option<BlockDataInfo>) {
+        self.changes.push(BlockChange::create(position, block));
+    }
+
+    pub fn get_changes(&self) -> &Vec<BlockChange> {
+        &self.changes
+    }
+
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Applies the changes inside the chunk.
+    ///
+    /// Changes outside of the chunk or its existing sections are returned,
+    /// so the caller can apply them to the neighbours.
+    pub fn apply(&self, chunk_position: &ChunkPosition, chunk_data: &mut ChunkData) -> Vec<BlockChange> {
+        let max_y = chunk_data.len() as i64 * CHUNK_SIZE as i64;
+
+        let mut rest = Vec::new();
+        for change in self.changes.iter() {
+            let position = change.position;
+            if position.get_chunk_position() != *chunk_position || position.get_y() < 0 || position.get_y() >= max_y {
+                rest.push(*change);
+                continue;
+            }
+            let (section, block_position) = position.get_block_position();
+            chunk_data.change_block(section, &block_position, change.block);
+        }
+        rest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChunkDelta;
+    use crate::chunks::{
+        block_position::BlockPosition,
+        chunk_data::{BlockDataInfo, ChunkData, ChunkSectionData},
+        chunk_position::ChunkPosition,
+    };
+
+    #[test]
+    fn test_chunk_delta_apply() {
+        let mut chunk_data = ChunkData::default();
+        chunk_data.push_section(ChunkSectionData::default());
+
+        let mut delta = ChunkDelta::new();
+        delta.set(BlockPosition::new(17, 2, 3), Some(BlockDataInfo::create(1)));
+        delta.set(BlockPosition::new(17, 2, 3), None);
+        delta.set(BlockPosition::new(20, 5, 0), Some(BlockDataInfo::create(2)));
+        delta.set(BlockPosition::new(0, 5, 0), Some(BlockDataInfo::create(2)));
+        delta.set(BlockPosition::new(20, 40, 0), Some(BlockDataInfo::create(2)));
+
+        let rest = delta.apply(&ChunkPosition::new(1, 0), &mut chunk_data);
+        assert_eq!(rest.len(), 2);
+        assert_eq!(chunk_data.get_block_info(&BlockPosition::new(17, 2, 3)), None);
+        assert_eq!(
+            chunk_data.get_block_info(&BlockPosition::new(20, 5, 0)),
+            Some(BlockDataInfo::create(2))
+        );
+    }
+}
@@ -1,5 +1,9 @@
 pub mod block_position;
+pub mod chunk_collider;
 pub mod chunk_data;
+pub mod chunk_delta;
 pub mod chunk_position;
 pub mod position;
+pub mod raycast;
 pub mod rotation;
+pub mod structure_template;
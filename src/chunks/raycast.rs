@@ -0,0 +1,372 @@
+//! Voxel raycast for block picking and line of sight checks.
+use crate::blocks::{
+    block_type::{BlockType, ColliderType, CubeFace},
+    voxel_visibility::VoxelVisibility,
+};
+
+use super::{
+    block_position::{BlockPosition, BlockPositionTrait},
+    chunk_data::{BlockDataInfo, BlockIndexType, ChunkData},
+    chunk_position::ChunkPosition,
+    position::Vector3,
+};
+
+/// Longest ray walked by [`raycast`], larger distances are clamped to it
+pub const MAX_RAYCAST_DISTANCE: f32 = 1024.0;
+
+/// Blocks which stop the ray; all blocks are hit by default.
+#[derive(Default, Clone, Debug)]
+pub struct RaycastFilter {
+    visibilities: Option<Vec<VoxelVisibility>>,
+    collider_types: Option<Vec<ColliderType>>,
+}
+
+impl RaycastFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Solid blocks only, for line of sight checks
+    pub fn solid() -> Self {
+        Self::new().collider_types(vec![ColliderType::Solid])
+    }
+
+    pub fn visibilities(mut self, visibilities: Vec<VoxelVisibility>) -> Self {
+        self.visibilities = Some(visibilities);
+        self
+    }
+
+    pub fn collider_types(mut self, collider_types: Vec<ColliderType>) -> Self {
+        self.collider_types = Some(collider_types);
+        self
+    }
+
+    pub fn is_hit(&self, block_type: &BlockType) -> bool {
+        let visibility = block_type.get_block_content().get_voxel_visibility();
+        if visibility == VoxelVisibility::Empty {
+            return false;
+        }
+        if let Some(visibilities) = self.visibilities.as_ref() {
+            if !visibilities.contains(&visibility) {
+                return false;
+            }
+        }
+        if let Some(collider_types) = self.collider_types.as_ref() {
+            if !collider_types.contains(block_type.get_collider_type()) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RaycastHit {
+    position: BlockPosition,
+    face: Option<CubeFace>,
+    point: Vector3,
+    distance: f32,
+    block: BlockDataInfo,
+}
+
+impl RaycastHit {
+    pub fn get_position(&self) -> &BlockPosition {
+        &self.position
+    }
+
+    /// Face of the block hit by the ray; `None` if the ray starts inside the block
+    pub fn get_face(&self) -> Option<CubeFace> {
+        self.face
+    }
+
+    /// Exact point on the block surface
+    pub fn get_point(&self) -> &Vector3 {
+        &self.point
+    }
+
+    pub fn get_distance(&self) -> f32 {
+        self.distance
+    }
+
+    pub fn get_block(&self) -> &BlockDataInfo {
+        &self.block
+    }
+
+    /// Position next to the hit face, where a new block is placed
+    pub fn get_place_position(&self) -> Option<BlockPosition> {
+        let [x, y, z] = self.face?.get_normal();
+        Some(self.position.offset(x, y, z))
+    }
+}
+
+/// Walks the voxels along the ray (DDA) and returns the first block passing the filter.
+///
+/// Missing chunks and sections are passed through as empty. The ray is tested against
+/// the shape boxes of the block, so it can pass over a slab.
+///
+/// Returns `None` if the origin, the direction or the distance is not finite.
+pub fn raycast<'a>(
+    origin: &Vector3,
+    direction: &Vector3,
+    max_distance: f32,
+    filter: &RaycastFilter,
+    get_chunk: impl Fn(&ChunkPosition) -> Option<&'a ChunkData>,
+    get_block_type: impl Fn(BlockIndexType) -> Option<&'a BlockType>,
+) -> Option<RaycastHit> {
+    let direction = [direction.x, direction.y, direction.z];
+    let origin = [origin.x, origin.y, origin.z];
+    let finite = origin.iter().chain(direction.iter()).all(|v| v.is_finite());
+    // Scaled down first, so the squares of the large components don't overflow
+    let scale = direction.iter().fold(0.0_f32, |m, v| m.max(v.abs()));
+    if !finite || !max_distance.is_finite() || scale == 0.0 {
+        return None;
+    }
+    let max_distance = max_distance.min(MAX_RAYCAST_DISTANCE);
+    let dir = direction.map(|d| d / scale);
+    let length = (dir[0] * dir[0] + dir[1] * dir[1] + dir[2] * dir[2]).sqrt();
+    let dir = dir.map(|d| d / length);
+
+    let mut voxel = origin.map(|v| v.floor() as i64);
+    let step = dir.map(|d| if d > 0.0 { 1 } else { -1 });
+    let t_delta = dir.map(|d| (1.0 / d).abs());
+    let mut t_max = [0, 1, 2].map(|i| {
+        if dir[i] == 0.0 {
+            return f32::INFINITY;
+        }
+        let boundary = if dir[i] > 0.0 {
+            voxel[i] as f32 + 1.0
+        } else {
+            voxel[i] as f32
+        };
+        (boundary - origin[i]) / dir[i]
+    });
+
+    let mut chunk: Option<(ChunkPosition, Option<&'a ChunkData>)> = None;
+    let mut t = 0.0;
+    while t <= max_distance {
+        let position = BlockPosition::new(voxel[0], voxel[1], voxel[2]);
+        let chunk_position = position.get_chunk_position();
+        let chunk_data = match chunk {
+            Some((p, data)) if p == chunk_position => data,
+            _ => {
+                let data = get_chunk(&chunk_position);
+                chunk = Some((chunk_position, data));
+                data
+            }
+        };
+
+        let block = match chunk_data {
+            Some(chunk_data) if position.get_y() >= 0 => {
+                let (section, block_position) = position.get_block_position();
+                chunk_data
+                    .get(section as usize)
+                    .and_then(|s| s.get(&block_position).copied())
+            }
+            _ => None,
+        };
+        if let Some(block) = block {
+            if let Some(block_type) = get_block_type(block.get_id()).filter(|b| filter.is_hit(b)) {
+                let boxes = block_type
+                    .get_block_content()
+                    .get_shape()
+                    .get_rotated_boxes(block.get_face());
+                let offset = voxel.map(|v| v as f32);
+                let hit = boxes
+                    .iter()
+                    .filter_map(|b| {
+                        let min = [0, 1, 2].map(|i| b.min[i] + offset[i]);
+                        let max = [0, 1, 2].map(|i| b.max[i] + offset[i]);
+                        intersect_box(&origin, &dir, &min, &max)
+                    })
+                    .min_by(|a, b| a.0.total_cmp(&b.0));
+                if let Some((distance, face)) = hit.filter(|(d, _)| *d <= max_distance) {
+                    let point = [0, 1, 2].map(|i| origin[i] + dir[i] * distance);
+                    return Some(RaycastHit {
+                        position,
+                        face,
+                        point: Vector3::new(point[0], point[1], point[2]),
+                        distance,
+                        block,
+                    });
+                }
+            }
+        }
+
+        // Step into the next voxel through the nearest boundary
+        let axis = (0..3).min_by(|a, b| t_max[*a].total_cmp(&t_max[*b])).unwrap();
+        t = t_max[axis];
+        // Origin far outside of the world is saturated to the i64 limits
+        voxel[axis] = voxel[axis].checked_add(step[axis])?;
+        t_max[axis] += t_delta[axis];
+    }
+    None
+}
+
+/// Slab test of the ray and the box; returns the distance and the entered face
+fn intersect_box(origin: &[f32; 3], dir: &[f32; 3], min: &[f32; 3], max: &[f32; 3]) -> Option<(f32, Option<CubeFace>)> {
+    let mut t_near = f32::NEG_INFINITY;
+    let mut t_far = f32::INFINITY;
+    let mut face = None;
+    for i in 0..3 {
+        if dir[i] == 0.0 {
+            if origin[i] < min[i] || origin[i] > max[i] {
+                return None;
+            }
+            continue;
+        }
+        let t1 = (min[i] - origin[i]) / dir[i];
+        let t2 = (max[i] - origin[i]) / dir[i];
+        let (t_enter, t_exit) = (t1.min(t2), t1.max(t2));
+        if t_enter > t_near {
+            t_near = t_enter;
+            // Moving in the positive direction enters through the negative face
+            face = CubeFace::from_index(if dir[i] > 0.0 { i } else { i + 3 });
+        }
+        t_far = t_far.min(t_exit);
+    }
+    if t_near > t_far || t_far < 0.0 {
+        return None;
+    }
+    match t_near < 0.0 {
+        true => Some((0.0, None)),
+        false => Some((t_near, face)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{raycast, RaycastFilter, MAX_RAYCAST_DISTANCE};
+    use crate::{
+        blocks::{
+            block_shape::BlockShape,
+            block_type::{BlockContent, BlockType, CubeFace},
+            voxel_visibility::VoxelVisibility,
+        },
+        chunks::{
+            block_position::{BlockPosition, ChunkBlockPosition},
+            chunk_data::{BlockDataInfo, ChunkData, ChunkSectionData},
+            chunk_position::ChunkPosition,
+            position::Vector3,
+        },
+    };
+
+    fn block_types() -> Vec<BlockType> {
+        let glass = BlockContent::single("default://glass.png").voxel_visibility(VoxelVisibility::Translucent);
+        vec![
+            BlockType::new(BlockContent::single("default://stone.png")),
+            BlockType::new(BlockContent::single("default://slab.png").shape(BlockShape::Slab)),
+            BlockType::new(glass),
+        ]
+    }
+
+    fn chunk(blocks: &[([u8; 3], u16)]) -> ChunkData {
+        let mut section = ChunkSectionData::default();
+        for ([x, y, z], id) in blocks {
+            section.insert(&ChunkBlockPosition::new(*x, *y, *z), BlockDataInfo::create(*id));
+        }
+        let mut chunk_data = ChunkData::default();
+        chunk_data.push_section(section);
+        chunk_data
+    }
+
+    #[test]
+    fn test_raycast() {
+        let block_types = block_types();
+        let center = chunk(&[([2, 0, 0], 2), ([4, 0, 0], 1), ([6, 0, 0], 0)]);
+        let west = chunk(&[([13, 0, 0], 0)]);
+        let get_chunk = |p: &ChunkPosition| match (p.x, p.z) {
+            (0, 0) => Some(&center),
+            (-1, 0) => Some(&west),
+            _ => None,
+        };
+        let get_block_type = |id| block_types.get(id as usize);
+
+        let origin = Vector3::new(0.5, 0.75, 0.5);
+        let direction = Vector3::new(1.0, 0.0, 0.0);
+        let hit = raycast(
+            &origin,
+            &direction,
+            10.0,
+            &RaycastFilter::new(),
+            get_chunk,
+            get_block_type,
+        )
+        .unwrap();
+        assert_eq!(*hit.get_position(), BlockPosition::new(2, 0, 0));
+        assert_eq!(hit.get_face(), Some(CubeFace::NegX));
+        assert_eq!(*hit.get_point(), Vector3::new(2.0, 0.75, 0.5));
+        assert_eq!(hit.get_place_position(), Some(BlockPosition::new(1, 0, 0)));
+
+        // Glass is skipped and the ray passes over the slab
+        let opaque = RaycastFilter::new().visibilities(vec![VoxelVisibility::Opaque]);
+        let hit = raycast(&origin, &direction, 10.0, &opaque, get_chunk, get_block_type).unwrap();
+        assert_eq!(*hit.get_position(), BlockPosition::new(6, 0, 0));
+        assert_eq!(hit.get_distance(), 5.5);
+        assert!(raycast(&origin, &direction, 5.0, &opaque, get_chunk, get_block_type).is_none());
+
+        // Slab top is hit from above
+        let down = Vector3::new(0.0, -1.0, 0.0);
+        let hit = raycast(
+            &Vector3::new(4.5, 3.0, 0.5),
+            &down,
+            10.0,
+            &opaque,
+            get_chunk,
+            get_block_type,
+        )
+        .unwrap();
+        assert_eq!(hit.get_face(), Some(CubeFace::PosY));
+        assert_eq!(hit.get_point().y, 0.5);
+
+        // Crossing into the negative chunk
+        let left = Vector3::new(-1.0, 0.0, 0.0);
+        let hit = raycast(&origin, &left, 10.0, &opaque, get_chunk, get_block_type).unwrap();
+        assert_eq!(*hit.get_position(), BlockPosition::new(-3, 0, 0));
+        assert_eq!(hit.get_face(), Some(CubeFace::PosX));
+
+        // Origin inside the block
+        let inside = Vector3::new(6.5, 0.5, 0.5);
+        let hit = raycast(&inside, &direction, 10.0, &opaque, get_chunk, get_block_type).unwrap();
+        assert_eq!((hit.get_distance(), hit.get_face()), (0.0, None));
+    }
+
+    #[test]
+    fn test_raycast_not_finite() {
+        let block_types = block_types();
+        let center = chunk(&[]);
+        let get_chunk = |_: &ChunkPosition| Some(&center);
+        let get_block_type = |id| block_types.get(id as usize);
+        let filter = RaycastFilter::new();
+
+        let origin = Vector3::new(0.5, 0.5, 0.5);
+        let direction = Vector3::new(1.0, 0.0, 0.0);
+        for max_distance in [f32::INFINITY, f32::NAN] {
+            assert!(raycast(&origin, &direction, max_distance, &filter, get_chunk, get_block_type).is_none());
+        }
+        let nan = Vector3::new(f32::NAN, 0.5, 0.5);
+        assert!(raycast(&nan, &direction, 10.0, &filter, get_chunk, get_block_type).is_none());
+        assert!(raycast(&origin, &nan, 10.0, &filter, get_chunk, get_block_type).is_none());
+        let infinite = Vector3::new(f32::INFINITY, 0.0, 0.0);
+        assert!(raycast(&origin, &infinite, 10.0, &filter, get_chunk, get_block_type).is_none());
+
+        // Huge but finite values
+        let far_origin = Vector3::new(1e20, 0.5, 0.5);
+        assert!(raycast(&far_origin, &direction, 10.0, &filter, get_chunk, get_block_type).is_none());
+        let far_origin = Vector3::new(-1e20, 0.5, 0.5);
+        let left = Vector3::new(-1.0, 0.0, 0.0);
+        assert!(raycast(&far_origin, &left, 10.0, &filter, get_chunk, get_block_type).is_none());
+        let stone = chunk(&[([2, 0, 0], 0)]);
+        let get_stone_chunk = |_: &ChunkPosition| Some(&stone);
+        let long = Vector3::new(1e20, 0.0, 0.0);
+        let hit = raycast(&origin, &long, 10.0, &filter, get_stone_chunk, get_block_type).unwrap();
+        assert_eq!(*hit.get_position(), BlockPosition::new(2, 0, 0));
+
+        // Blocks past the max distance are not reached
+        let far = chunk(&[([0, 0, 0], 0)]);
+        let get_chunk = |p: &ChunkPosition| match p.x {
+            x if x == (MAX_RAYCAST_DISTANCE as i64 + 16) / 16 => Some(&far),
+            _ => None,
+        };
+        assert!(raycast(&origin, &direction, 1e9, &filter, get_chunk, get_block_type).is_none());
+    }
+}
@@ -0,0 +1,585 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    blocks::block_info::BlockFace,
+    utils::{compressable::Compressable, fix_chunk_loc_pos},
+    CHUNK_SIZE,
+};
+
+use super::{
+    block_position::{BlockPosition, BlockPositionTrait},
+    chunk_data::{BlockColorType, BlockDataInfo, BlockIndexType, ChunkData},
+    chunk_position::ChunkPosition,
+};
+
+/// Must be increased every time the encoded layout of [`StructureTemplate`] changes.
+pub const STRUCTURE_FORMAT_VERSION: u16 = 1;
+
+/// Blocks refer to the palette by `u16` index
+pub const MAX_PALETTE_SIZE: usize = u16::MAX as usize;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MirrorAxis {
+    X,
+    Z,
+}
+
+/// Block of the template.
+///
+/// Refers to the template palette instead of the block id,
+/// so it doesn't depend on the id map of the world it was captured from.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StructureBlock {
+    palette_index: u16,
+    face: Option<BlockFace>,
+    color: Option<BlockColorType>,
+}
+
+impl StructureBlock {
+    pub fn get_face(&self) -> &Option<BlockFace> {
+        &self.face
+    }
+
+    pub fn get_color(&self) -> &Option<BlockColorType> {
+        &self.color
+    }
+}
+
+/// Rectangular box of blocks which can be captured from chunks and placed back.
+///
+/// Used by builders, world generators and plugins for multi-block builds.
+/// [`Compressable::compress`] output is used as the structure file content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructureTemplate {
+    version: u16,
+    size: [u32; 3],
+    palette: Vec<String>,
+    blocks: Vec<Option<StructureBlock>>,
+}
+
+impl StructureTemplate {
+    pub fn create(size_x: u32, size_y: u32, size_z: u32) -> Result<Self, String> {
+        let Some(volume) = get_volume([size_x, size_y, size_z]) else {
+            return Err(format!("Structure size {}x{}x{} is too large", size_x, size_y, size_z));
+        };
+        Ok(Self {
+            version: STRUCTURE_FORMAT_VERSION,
+            size: [size_x, size_y, size_z],
+            palette: Default::default(),
+            blocks: vec![None; volume],
+        })
+    }
+
+    /// Captures all blocks between two corners (both inclusive).
+    ///
+    /// `get_chunk` must return the data of every chunk the box touches.
+    pub fn capture<'a>(
+        from: &BlockPosition,
+        to: &BlockPosition,
+        block_id_map: &BTreeMap<BlockIndexType, String>,
+        get_chunk: impl Fn(&ChunkPosition) -> Option<&'a ChunkData>,
+    ) -> Result<Self, String> {
+        let min = BlockPosition::new(
+            from.get_x().min(to.get_x()),
+            from.get_y().min(to.get_y()),
+            from.get_z().min(to.get_z()),
+        );
+        let max = BlockPosition::new(
+            from.get_x().max(to.get_x()),
+            from.get_y().max(to.get_y()),
+            from.get_z().max(to.get_z()),
+        );
+        let axis_size = |min: i64, max: i64| {
+            max.checked_sub(min)
+                .and_then(|d| d.checked_add(1))
+                .and_then(|d| u32::try_from(d).ok())
+                .ok_or_else(|| format!("Structure box {} - {} is too large", min, max))
+        };
+        let mut template = Self::create(
+            axis_size(min.get_x(), max.get_x())?,
+            axis_size(min.get_y(), max.get_y())?,
+            axis_size(min.get_z(), max.get_z())?,
+        )?;
+
+        for (x, y, z) in template.iter_coords() {
+            let position = min.offset(x as i64, y as i64, z as i64);
+            let chunk_position = position.get_chunk_position();
+            let Some(chunk_data) = get_chunk(&chunk_position) else {
+                return Err(format!("chunk {} is not loaded", chunk_position));
+            };
+            let Some(block_info) = read_block(chunk_data, &position) else {
+                continue;
+            };
+            let Some(slug) = block_id_map.get(&block_info.get_id()) else {
+                return Err(format!(
+                    "block id #{} is not found in the block id map",
+                    block_info.get_id()
+                ));
+            };
+            template.set_block(x, y, z, slug, *block_info.get_face(), *block_info.get_color())?;
+        }
+        Ok(template)
+    }
+
+    pub fn get_size(&self) -> [u32; 3] {
+        self.size
+    }
+
+    pub fn get_palette(&self) -> &Vec<String> {
+        &self.palette
+    }
+
+    /// Returns `None` if the position is out of the template size.
+    fn index(&self, x: u32, y: u32, z: u32) -> Option<usize> {
+        if x >= self.size[0] || y >= self.size[1] || z >= self.size[2] {
+            return None;
+        }
+        Some(self.coords_index(x, y, z))
+    }
+
+    /// Index of the position returned by [`Self::iter_coords`]; the volume fits into usize.
+    fn coords_index(&self, x: u32, y: u32, z: u32) -> usize {
+        let [size_x, size_y, _] = self.size.map(|s| s as usize);
+        x as usize + size_x * (y as usize + size_y * z as usize)
+    }
+
+    fn out_of_range(&self, x: u32, y: u32, z: u32) -> String {
+        format!("Position {},{},{} is out of the template size {:?}", x, y, z, self.size)
+    }
+
+    fn iter_coords(&self) -> impl Iterator<Item = (u32, u32, u32)> {
+        let [size_x, size_y, size_z] = self.size;
+        (0..size_z).flat_map(move |z| (0..size_y).flat_map(move |y| (0..size_x).map(move |x| (x, y, z))))
+    }
+
+    fn get_palette_index(&mut self, slug: &str) -> Result<u16, String> {
+        if let Some(i) = self.palette.iter().position(|s| s == slug) {
+            return Ok(i as u16);
+        }
+        if self.palette.len() >= MAX_PALETTE_SIZE {
+            return Err(format!(
+                "Structure palette can't have more than {} blocks",
+                MAX_PALETTE_SIZE
+            ));
+        }
+        self.palette.push(slug.to_string());
+        Ok((self.palette.len() - 1) as u16)
+    }
+
+    pub fn set_block(
+        &mut self,
+        x: u32,
+        y: u32,
+        z: u32,
+        slug: &str,
+        face: Option<BlockFace>,
+        color: Option<BlockColorType>,
+    ) -> Result<(), String> {
+        let Some(idx) = self.index(x, y, z) else {
+            return Err(self.out_of_range(x, y, z));
+        };
+        let palette_index = self.get_palette_index(slug)?;
+        self.blocks[idx] = Some(StructureBlock {
+            palette_index,
+            face,
+            color,
+        });
+        Ok(())
+    }
+
+    pub fn remove_block(&mut self, x: u32, y: u32, z: u32) -> Result<(), String> {
+        let Some(idx) = self.index(x, y, z) else {
+            return Err(self.out_of_range(x, y, z));
+        };
+        self.blocks[idx] = None;
+        Ok(())
+    }
+
+    /// Returns block slug and its placement data
+    ///
+    /// `None` if the position is empty or out of the template size.
+    pub fn get_block(&self, x: u32, y: u32, z: u32) -> Option<(&String, &StructureBlock)> {
+        let block = self.blocks[self.index(x, y, z)?].as_ref()?;
+        Some((&self.palette[block.palette_index as usize], block))
+    }
+
+    /// Number of non-empty blocks
+    pub fn len(&self) -> usize {
+        self.blocks.iter().filter(|b| b.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.iter().all(|b| b.is_none())
+    }
+
+    /// Returns the template rotated so that its south side is facing `face`.
+    ///
+    /// The rotation follows [`BlockFace::get_turns`]: `South` keeps the template as is.
+    pub fn rotated(&self, face: BlockFace) -> Self {
+        let turns = face.get_turns();
+        let mut template = self.clone();
+        for _ in 0..turns {
+            template = template.rotated_left();
+        }
+        template
+    }
+
+    fn rotated_left(&self) -> Self {
+        let [size_x, size_y, size_z] = self.size;
+        let mut template = Self {
+            version: self.version,
+            size: [size_z, size_y, size_x],
+            palette: self.palette.clone(),
+            blocks: vec![None; self.blocks.len()],
+        };
+        for (x, y, z) in self.iter_coords() {
+            let Some(mut block) = self.blocks[self.coords_index(x, y, z)] else {
+                continue;
+            };
+            block.face = block.face.map(|f| f.rotate_left());
+            let idx = template.coords_index(size_z - 1 - z, y, x);
+            template.blocks[idx] = Some(block);
+        }
+        template
+    }
+
+    pub fn mirrored(&self, axis: MirrorAxis) -> Self {
+        let [size_x, _size_y, size_z] = self.size;
+        let mut template = self.clone();
+        for (x, y, z) in self.iter_coords() {
+            let block = self.blocks[self.coords_index(x, y, z)].map(|mut b| {
+                b.face = b.face.map(|f| mirror_face(f, axis));
+                b
+            });
+            let idx = match axis {
+                MirrorAxis::X => template.coords_index(size_x - 1 - x, y, z),
+                MirrorAxis::Z => template.coords_index(x, y, size_z - 1 - z),
+            };
+            template.blocks[idx] = block;
+        }
+        template
+    }
+
+    /// All chunks which will be touched by placing the template at `origin`.
+    pub fn get_affected_chunks(&self, origin: &BlockPosition) -> Vec<ChunkPosition> {
+        let min_x = fix_chunk_loc_pos(origin.get_x());
+        let min_z = fix_chunk_loc_pos(origin.get_z());
+        let max_x = fix_chunk_loc_pos(origin.get_x() + self.size[0] as i64 - 1);
+        let max_z = fix_chunk_loc_pos(origin.get_z() + self.size[2] as i64 - 1);
+
+        let mut chunks = Vec::new();
+        for x in min_x..=max_x {
+            for z in min_z..=max_z {
+                chunks.push(ChunkPosition::new(x, z));
+            }
+        }
+        chunks
+    }
+
+    /// Places the part of the template which overlaps the chunk.
+    ///
+    /// `origin` is the global position of the template minimum corner.
+    /// If `replace_air` is set, empty template blocks remove the chunk blocks.
+    /// Blocks outside of existing chunk sections are skipped.
+    ///
+    /// Returns the number of changed blocks.
+    pub fn place(
+        &self,
+        origin: &BlockPosition,
+        chunk_position: &ChunkPosition,
+        chunk_data: &mut ChunkData,
+        block_id_map: &BTreeMap<BlockIndexType, String>,
+        replace_air: bool,
+    ) -> Result<usize, String> {
+        let ids = self.resolve_palette(block_id_map)?;
+
+        let chunk_x = chunk_position.x * CHUNK_SIZE as i64;
+        let chunk_z = chunk_position.z * CHUNK_SIZE as i64;
+        let max_y = chunk_data.len() as i64 * CHUNK_SIZE as i64;
+
+        let mut changed = 0;
+        for (x, y, z) in self.iter_coords() {
+            let position = origin.offset(x as i64, y as i64, z as i64);
+            if position.get_x() < chunk_x
+                || position.get_x() >= chunk_x + CHUNK_SIZE as i64
+                || position.get_z() < chunk_z
+                || position.get_z() >= chunk_z + CHUNK_SIZE as i64
+                || position.get_y() < 0
+                || position.get_y() >= max_y
+            {
+                continue;
+            }
+
+            let block_info = match self.blocks[self.coords_index(x, y, z)] {
+                Some(block) => {
+                    let mut block_info = BlockDataInfo::create(ids[block.palette_index as usize]);
+                    block_info.set_face(block.face);
+                    if let Some(color) = block.color {
+                        block_info = block_info.color(color);
+                    }
+                    Some(block_info)
+                }
+                None if replace_air => None,
+                None => continue,
+            };
+
+            let (section, block_position) = position.get_block_position();
+            chunk_data.change_block(section, &block_position, block_info);
+            changed += 1;
+        }
+        Ok(changed)
+    }
+
+    fn resolve_palette(&self, block_id_map: &BTreeMap<BlockIndexType, String>) -> Result<Vec<BlockIndexType>, String> {
+        let ids: HashMap<&String, BlockIndexType> = block_id_map.iter().map(|(id, slug)| (slug, *id)).collect();
+        let mut result = Vec::with_capacity(self.palette.len());
+        for slug in self.palette.iter() {
+            let Some(id) = ids.get(slug) else {
+                return Err(format!("block \"{}\" is not found in the block id map", slug));
+            };
+            result.push(*id);
+        }
+        Ok(result)
+    }
+}
+
+impl Compressable for StructureTemplate {
+    fn decode(encoded: Vec<u8>) -> Result<Self, String> {
+        let template: Self = bincode::deserialize(&encoded).map_err(|e| format!("Decode error: {}", e))?;
+        if template.version != STRUCTURE_FORMAT_VERSION {
+            return Err(format!(
+                "Structure format version {} is not supported; current version is {}",
+                template.version, STRUCTURE_FORMAT_VERSION
+            ));
+        }
+        if get_volume(template.size) != Some(template.blocks.len()) {
+            return Err(format!("Structure blocks count doesn't match size {:?}", template.size));
+        }
+        if template.palette.len() > MAX_PALETTE_SIZE {
+            return Err(format!(
+                "Structure palette size {} is too large",
+                template.palette.len()
+            ));
+        }
+        for block in template.blocks.iter().flatten() {
+            if block.palette_index as usize >= template.palette.len() {
+                return Err(format!(
+                    "Structure palette index {} is out of range",
+                    block.palette_index
+                ));
+            }
+        }
+        Ok(template)
+    }
+}
+
+fn get_volume(size: [u32; 3]) -> Option<usize> {
+    let [size_x, size_y, size_z] = size.map(|s| s as usize);
+    size_x.checked_mul(size_y)?.checked_mul(size_z)
+}
+
+fn mirror_face(face: BlockFace, axis: MirrorAxis) -> BlockFace {
+    match (axis, face) {
+        (MirrorAxis::X, BlockFace::East) => BlockFace::West,
+        (MirrorAxis::X, BlockFace::West) => BlockFace::East,
+        (MirrorAxis::Z, BlockFace::North) => BlockFace::South,
+        (MirrorAxis::Z, BlockFace::South) => BlockFace::North,
+        (_, f) => f,
+    }
+}
+
+fn read_block(chunk_data: &ChunkData, position: &BlockPosition) -> Option<BlockDataInfo> {
+    if position.get_y() < 0 {
+        return None;
+    }
+    let (section, block_position) = position.get_block_position();
+    chunk_data.get(section as usize)?.get(&block_position).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{MirrorAxis, StructureTemplate, MAX_PALETTE_SIZE};
+    use crate::{
+        blocks::{
+            block_info::BlockFace,
+            block_shape::BlockShape,
+            block_type::{BlockContent, BlockType, CubeFace},
+            texture_atlas::TextureAtlasBuilder,
+        },
+        chunks::{
+            block_position::{BlockPosition, ChunkBlockPosition},
+            chunk_data::{BlockDataInfo, ChunkData, ChunkSectionData},
+            chunk_position::ChunkPosition,
+        },
+        utils::compressable::Compressable,
+    };
+
+    fn block_id_map() -> BTreeMap<u16, String> {
+        let mut map = BTreeMap::new();
+        map.insert(1, "grass".to_string());
+        map.insert(2, "stone".to_string());
+        map
+    }
+
+    fn chunk() -> ChunkData {
+        let mut chunk_data = ChunkData::default();
+        chunk_data.push_section(ChunkSectionData::default());
+        chunk_data
+    }
+
+    #[test]
+    fn test_rotate() {
+        let mut template = StructureTemplate::create(3, 1, 2).unwrap();
+        template
+            .set_block(0, 0, 0, "stone", Some(BlockFace::South), None)
+            .unwrap();
+
+        let rotated = template.rotated(BlockFace::West);
+        assert_eq!(rotated.get_size(), [2, 1, 3]);
+        let (slug, block) = rotated.get_block(1, 0, 0).unwrap();
+        assert_eq!(slug, "stone");
+        assert_eq!(*block.get_face(), Some(BlockFace::West));
+
+        let full_turn = template.rotated(BlockFace::East).rotated(BlockFace::West);
+        assert_eq!(full_turn.get_size(), [3, 1, 2]);
+        assert!(full_turn.get_block(0, 0, 0).is_some());
+    }
+
+    #[test]
+    fn test_rotate_stairs() {
+        // Stairs with the back against the wall at -Z
+        let mut template = StructureTemplate::create(1, 1, 2).unwrap();
+        template.set_block(0, 0, 0, "stone", None, None).unwrap();
+        template
+            .set_block(0, 0, 1, "stairs", Some(BlockFace::South), None)
+            .unwrap();
+
+        // The wall is moved to +X
+        let rotated = template.rotated(BlockFace::West);
+        assert_eq!(rotated.get_size(), [2, 1, 1]);
+        assert_eq!(rotated.get_block(1, 0, 0).unwrap().0, "stone");
+        let (slug, block) = rotated.get_block(0, 0, 0).unwrap();
+        assert_eq!(slug, "stairs");
+        assert_eq!(*block.get_face(), Some(BlockFace::West));
+
+        // Upper step of the stairs is at the wall side
+        let boxes = BlockShape::Stairs.get_rotated_boxes(block.get_face());
+        assert_eq!((boxes[1].min[0], boxes[1].max[0]), (0.5, 1.0));
+        assert_eq!((boxes[1].min[2], boxes[1].max[2]), (0.0, 1.0));
+
+        // Back texture is seen from the wall side
+        let stairs = BlockType::new(
+            BlockContent::single("default://stairs.png")
+                .shape(BlockShape::Stairs)
+                .face_texture(CubeFace::NegZ, "default://stairs_back.png"),
+        );
+        let atlas = TextureAtlasBuilder::new()
+            .build([stairs].iter(), |_| Ok([16, 16]))
+            .unwrap();
+        let back = atlas
+            .get_rotated_face("stairs", CubeFace::PosX, block.get_face())
+            .unwrap();
+        assert_eq!(Some(back.texture), atlas.get_uv("default://stairs_back.png"));
+    }
+
+    #[test]
+    fn test_mirror() {
+        let mut template = StructureTemplate::create(3, 1, 1).unwrap();
+        template
+            .set_block(0, 0, 0, "stone", Some(BlockFace::East), None)
+            .unwrap();
+
+        let mirrored = template.mirrored(MirrorAxis::X);
+        assert!(mirrored.get_block(0, 0, 0).is_none());
+        let (_, block) = mirrored.get_block(2, 0, 0).unwrap();
+        assert_eq!(*block.get_face(), Some(BlockFace::West));
+    }
+
+    #[test]
+    fn test_place_across_chunks() {
+        let mut template = StructureTemplate::create(2, 1, 1).unwrap();
+        template.set_block(0, 0, 0, "stone", None, None).unwrap();
+        template.set_block(1, 0, 0, "grass", None, None).unwrap();
+
+        let origin = BlockPosition::new(-1, 5, 3);
+        let chunks = template.get_affected_chunks(&origin);
+        assert_eq!(chunks, vec![ChunkPosition::new(-1, 0), ChunkPosition::new(0, 0)]);
+
+        let mut left = chunk();
+        let changed = template
+            .place(&origin, &chunks[0], &mut left, &block_id_map(), false)
+            .unwrap();
+        assert_eq!(changed, 1);
+        let block = left.get(0).unwrap().get(&ChunkBlockPosition::new(15, 5, 3)).unwrap();
+        assert_eq!(block.get_id(), 2);
+
+        let mut right = chunk();
+        template
+            .place(&origin, &chunks[1], &mut right, &block_id_map(), false)
+            .unwrap();
+        let block = right.get(0).unwrap().get(&ChunkBlockPosition::new(0, 5, 3)).unwrap();
+        assert_eq!(block.get_id(), 1);
+    }
+
+    #[test]
+    fn test_capture_and_decode() {
+        let mut chunk_data = chunk();
+        chunk_data.change_block(
+            0,
+            &ChunkBlockPosition::new(1, 2, 3),
+            Some(BlockDataInfo::create(2).face(BlockFace::North)),
+        );
+
+        let template = StructureTemplate::capture(
+            &BlockPosition::new(2, 3, 4),
+            &BlockPosition::new(0, 0, 0),
+            &block_id_map(),
+            |_| Some(&chunk_data),
+        )
+        .unwrap();
+        assert_eq!(template.get_size(), [3, 4, 5]);
+        assert_eq!(template.len(), 1);
+
+        let decoded = StructureTemplate::decode(template.encode()).unwrap();
+        let (slug, block) = decoded.get_block(1, 2, 3).unwrap();
+        assert_eq!(slug, "stone");
+        assert_eq!(*block.get_face(), Some(BlockFace::North));
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let mut template = StructureTemplate::create(2, 2, 2).unwrap();
+        assert!(template.set_block(2, 0, 0, "stone", None, None).is_err());
+        assert!(template.remove_block(0, 0, 5).is_err());
+        assert!(template.get_block(0, 2, 0).is_none());
+
+        assert!(StructureTemplate::create(u32::MAX, u32::MAX, u32::MAX).is_err());
+    }
+
+    #[test]
+    fn test_palette_overflow() {
+        let mut template = StructureTemplate::create(1, 1, 1).unwrap();
+        template.palette = (0..MAX_PALETTE_SIZE).map(|i| format!("block_{}", i)).collect();
+        assert!(template.set_block(0, 0, 0, "block_7", None, None).is_ok());
+        assert!(template.set_block(0, 0, 0, "stone", None, None).is_err());
+        assert_eq!(template.get_block(0, 0, 0).unwrap().0, "block_7");
+    }
+
+    #[test]
+    fn test_capture_unknown_id() {
+        let mut chunk_data = chunk();
+        chunk_data.change_block(0, &ChunkBlockPosition::new(0, 0, 0), Some(BlockDataInfo::create(7)));
+
+        let result = StructureTemplate::capture(
+            &BlockPosition::new(0, 0, 0),
+            &BlockPosition::new(1, 1, 1),
+            &block_id_map(),
+            |_| Some(&chunk_data),
+        );
+        assert!(result.is_err());
+    }
+}
@@ -1,11 +1,12 @@
 use ahash::HashMap;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::slice::Iter;
 use std::str::FromStr;
 
 pub const REGEX_COMMAND: &str = r####"([\d\w$&+,:;=?@#|'<>.^*()%!-]*)|"([\d\w$&+,:;=?@#|'<>.^*()%!\- ]*)""####;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Command {
     name: String,
     subcommand_required: bool,
@@ -119,6 +120,20 @@ impl Command {
         Some((self, Some(&self.args[command_sequence.len() - 1])))
     }
 
+    /// Names of the subcommands matched by the sequence, like ["create"] for "create test"
+    pub fn get_subcommand_path(&self, command_sequence: &[String]) -> Vec<String> {
+        let mut path = Vec::new();
+        let mut command = self;
+        for name in command_sequence {
+            let Some(subcommand) = command.commands.iter().find(|c| c.name == *name) else {
+                break;
+            };
+            path.push(subcommand.name.clone());
+            command = subcommand;
+        }
+        path
+    }
+
     pub fn arg(mut self, arg: Arg) -> Self {
         self.args.push(arg);
         self
@@ -130,12 +145,15 @@ impl Command {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum ArgType {
     Choices(Vec<String>),
+
+    /// Choices are requested from the command owner on every completion
+    Dynamic,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Arg {
     name: String,
     required: bool,
@@ -168,9 +186,14 @@ impl Arg {
         self.arg_type = Some(ArgType::Choices(c));
         self
     }
+
+    pub fn dynamic(mut self) -> Self {
+        self.arg_type = Some(ArgType::Dynamic);
+        self
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CommandMatch {
     name: String,
     subcommand: Option<Box<CommandMatch>>,
@@ -355,6 +378,21 @@ mod tests {
         assert_eq!(result.as_ref().unwrap().1.as_ref().unwrap().name, "x".to_string());
     }
 
+    #[test]
+    fn test_command_serde() {
+        let command = world_command();
+        let json = serde_json::to_string(&command).unwrap();
+        let command: Command = serde_json::from_str(&json).unwrap();
+
+        let command_sequence = Command::parse_command(&"world create test".to_string());
+        let result = command.eval(&command_sequence[1..]).unwrap();
+        let json = serde_json::to_string(&result).unwrap();
+        let result: super::CommandMatch = serde_json::from_str(&json).unwrap();
+
+        let s = result.subcommand().as_ref().unwrap();
+        assert_eq!(s.get_arg::<String, _>("slug").unwrap(), "test".to_string());
+    }
+
     #[test]
     fn test_parse_command() {
         let command_sequence = Command::parse_command(&"tp ".to_string());
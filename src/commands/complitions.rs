@@ -1,6 +1,6 @@
 use crate::utils::string_remove_range;
 
-use super::command::{ArgType, Command};
+use super::command::{Arg, ArgType, Command};
 
 /// Requesting options for completing the console command
 #[derive(Clone, PartialEq)]
@@ -94,6 +94,19 @@ impl CompleteResponse {
     }
 
     pub fn complete<'a>(request: &CompleteRequest, commands: impl Iterator<Item = &'a Command>) -> CompleteResponse {
+        Self::complete_dynamic(request, commands, |_command, _subcommands, _arg| Vec::new())
+    }
+
+    /// Same as [`complete`](CompleteResponse::complete), but choices of [`ArgType::Dynamic`]
+    /// arguments are taken from `dynamic_choices`, which receives the root command,
+    /// the [`subcommand path`](Command::get_subcommand_path) and the argument.
+    ///
+    /// Server uses it to route the completion to the plugin which registered the command.
+    pub fn complete_dynamic<'a>(
+        request: &CompleteRequest,
+        commands: impl Iterator<Item = &'a Command>,
+        dynamic_choices: impl Fn(&Command, &[String], &Arg) -> Vec<String>,
+    ) -> CompleteResponse {
         let line = request.get_line().clone();
         let pos = request.get_pos().clone();
 
@@ -120,16 +133,16 @@ impl CompleteResponse {
             return complete_response;
         }
 
-        for command in commands {
+        for root_command in commands {
             // Find subcommand
-            if *command.get_name() != lead_command {
+            if *root_command.get_name() != lead_command {
                 continue;
             }
 
             let last_arg = command_sequence[command_sequence.len() - 1].clone();
             complete_response.set_offset(last_arg.len());
 
-            if let Some((command, arg)) = command.get_current_subcommand(&command_sequence[1..]) {
+            if let Some((command, arg)) = root_command.get_current_subcommand(&command_sequence[1..]) {
                 match arg {
                     Some(arg) => {
                         if let Some(arg_type) = arg.get_arg_type() {
@@ -141,6 +154,14 @@ impl CompleteResponse {
                                         }
                                     }
                                 }
+                                ArgType::Dynamic => {
+                                    let subcommands = root_command.get_subcommand_path(&command_sequence[1..]);
+                                    for choice in dynamic_choices(root_command, &subcommands, arg) {
+                                        if let Some(completion) = Completion::generate_completion(&last_arg, &choice) {
+                                            complete_response.add_completion(completion);
+                                        }
+                                    }
+                                }
                             }
                         }
                     }
@@ -196,6 +217,15 @@ mod tests {
             .arg(Arg::new("value".to_owned()).required(true));
         commands.push(c);
 
+        let c = Command::new("warp".to_string()).arg(Arg::new("name".to_owned()).required(true).dynamic());
+        commands.push(c);
+
+        let c = Command::new("home".to_string())
+            .subcommand_required(true)
+            .subcommand(Command::new("go".to_string()).arg(Arg::new("name".to_owned()).required(true).dynamic()))
+            .subcommand(Command::new("remove".to_string()).arg(Arg::new("name".to_owned()).required(true).dynamic()));
+        commands.push(c);
+
         commands
     }
 
@@ -217,6 +247,36 @@ mod tests {
         assert_eq!(caret_column, 4);
     }
 
+    #[test]
+    fn test_complete_dynamic() {
+        let request = CompleteRequest::create("warp s", 6);
+        let commands = get_commands();
+
+        let complitions = CompleteResponse::complete_dynamic(&request, commands.iter(), |command, subcommands, arg| {
+            assert_eq!(command.get_name(), "warp");
+            assert!(subcommands.is_empty());
+            assert_eq!(arg.get_name(), "name");
+            vec!["spawn".to_string(), "mine".to_string()]
+        });
+        assert_eq!(complitions.get_completions().len(), 1);
+        assert_eq!(complitions.get_completions()[0].get_completion(), "spawn");
+
+        let complitions = CompleteResponse::complete(&request, commands.iter());
+        assert_eq!(complitions.get_completions().len(), 0);
+
+        // Same named args of the different subcommands
+        let homes = |_: &Command, subcommands: &[String], _: &Arg| match subcommands {
+            [s] if s == "go" => vec!["house".to_string(), "hut".to_string()],
+            _ => vec!["hut".to_string()],
+        };
+        let request = CompleteRequest::create("home go h", 10);
+        let complitions = CompleteResponse::complete_dynamic(&request, commands.iter(), homes);
+        assert_eq!(complitions.get_completions().len(), 2);
+        let request = CompleteRequest::create("home remove h", 14);
+        let complitions = CompleteResponse::complete_dynamic(&request, commands.iter(), homes);
+        assert_eq!(complitions.get_completions().len(), 1);
+    }
+
     #[test]
     fn test_string_remove_range() {
         let input = string_remove_range("world", 0, 2);
@@ -1,4 +1,7 @@
-use crate::blocks::block_type::{BlockType, BlockTypeManifest};
+use crate::blocks::{
+    block_type::BlockType,
+    block_validator::{format_diagnostics, BlockManifestValidator},
+};
 
 /// Их необходимо хранить в общей библиотеки, т.к. их используют клиент и сервер
 /// Клиент загружает их по дефолту
@@ -6,14 +9,23 @@ use crate::blocks::block_type::{BlockType, BlockTypeManifest};
 
 pub fn generate_default_blocks() -> Result<Vec<BlockType>, String> {
     let text = include_str!("default_blocks.yml");
-    let m: Result<Vec<BlockTypeManifest>, serde_yaml::Error> = serde_yaml::from_str(text);
-
-    let m = match m {
+    let m = match BlockManifestValidator::new().validate_yaml(text) {
         Ok(m) => m,
-        Err(e) => return Err(format!("&cyaml parsing error: {}", e)),
+        Err(e) => return Err(format_diagnostics(&e)),
     };
     // println!("{}", serde_yaml::to_string(&m).unwrap());
 
-    let m: Vec<BlockType> = m.iter().map(|m| m.to_block()).collect();
-    Ok(m)
+    m.iter().map(|m| m.to_block()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::generate_default_blocks;
+
+    #[test]
+    fn test_default_blocks_valid() {
+        if let Err(e) = generate_default_blocks() {
+            panic!("{}", e);
+        }
+    }
 }
@@ -4,6 +4,7 @@ pub mod commands;
 pub mod default_blocks;
 pub mod default_blocks_ids;
 pub mod default_resources;
+pub mod resource_packs;
 pub mod utils;
 pub mod world_generator;
 pub mod worlds_storage;
@@ -0,0 +1,37 @@
+use serde::de::DeserializeOwned;
+
+use super::service::PluginService;
+
+#[cfg(not(feature = "mock-host"))]
+#[extism_pdk::host_fn]
+extern "ExtismHost" {
+    fn get_plugin_config_raw() -> String;
+}
+
+#[cfg(feature = "mock-host")]
+use crate::plugin_api::mock_host::host::get_plugin_config_raw;
+
+/// Yaml config of the plugin provided by the server administrator
+#[derive(Default)]
+pub struct PluginConfig;
+
+impl PluginService for PluginConfig {
+    fn inject() -> Self {
+        Self
+    }
+}
+
+impl PluginConfig {
+    /// `Null` if the plugin has no config
+    pub fn get_value(&self) -> Result<serde_yaml::Value, extism_pdk::Error> {
+        let raw = unsafe { get_plugin_config_raw()? };
+        if raw.trim().is_empty() {
+            return Ok(serde_yaml::Value::Null);
+        }
+        Ok(serde_yaml::from_str(&raw)?)
+    }
+
+    pub fn get<T: DeserializeOwned>(&self) -> Result<T, extism_pdk::Error> {
+        Ok(serde_yaml::from_value(self.get_value()?)?)
+    }
+}
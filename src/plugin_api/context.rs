@@ -0,0 +1,54 @@
+use super::{
+    config::PluginConfig, logger::PluginLogger, messaging::PluginMessenger, scheduler::PluginScheduler,
+    service::PluginService, storage::PluginStorage, worlds_manager::WorldsManager,
+};
+
+/// All host services in one argument
+#[derive(Default)]
+pub struct PluginContext {
+    worlds: WorldsManager,
+    logger: PluginLogger,
+    storage: PluginStorage,
+    config: PluginConfig,
+    scheduler: PluginScheduler,
+    messenger: PluginMessenger,
+}
+
+impl PluginService for PluginContext {
+    fn inject() -> Self {
+        Self {
+            worlds: WorldsManager::inject(),
+            logger: PluginLogger::inject(),
+            storage: PluginStorage::inject(),
+            config: PluginConfig::inject(),
+            scheduler: PluginScheduler::inject(),
+            messenger: PluginMessenger::inject(),
+        }
+    }
+}
+
+impl PluginContext {
+    pub fn get_worlds(&self) -> &WorldsManager {
+        &self.worlds
+    }
+
+    pub fn get_logger(&self) -> &PluginLogger {
+        &self.logger
+    }
+
+    pub fn get_storage(&self) -> &PluginStorage {
+        &self.storage
+    }
+
+    pub fn get_config(&self) -> &PluginConfig {
+        &self.config
+    }
+
+    pub fn get_scheduler(&self) -> &PluginScheduler {
+        &self.scheduler
+    }
+
+    pub fn get_messenger(&self) -> &PluginMessenger {
+        &self.messenger
+    }
+}
@@ -0,0 +1,315 @@
+//! Block types and resources registered by plugins in `on_plugin_load`.
+//!
+//! Plugin resources are stored under the plugin namespace: resource "blocks/ruby.png"
+//! of the plugin "gems" is available as "gems://blocks/ruby.png".
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::{
+    blocks::{
+        block_info::generate_block_id_map,
+        block_type::{BlockContent, BlockType, BlockTypeManifest},
+        block_validator::{format_diagnostics, BlockManifestValidator},
+    },
+    chunks::chunk_data::BlockIndexType,
+    default_resources::DEFAULT_RESOURCES,
+    resource_packs::uri::ResourceUri,
+    utils::{compressable::Compressable, split_resource_path},
+};
+
+const REGEX_BLOCK_SLUG: &str = r"^[a-z0-9_]{2,64}$";
+
+/// Wire type of the resource registration; sent as [`Compressable`] bytes
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RegisterResourceRequest {
+    path: String,
+    data: Vec<u8>,
+}
+
+impl Compressable for RegisterResourceRequest {}
+
+impl RegisterResourceRequest {
+    /// Path inside the plugin namespace, like "blocks/ruby.png"
+    pub fn create(path: impl Into<String>, data: Vec<u8>) -> Self {
+        Self {
+            path: path.into(),
+            data,
+        }
+    }
+
+    pub fn get_path(&self) -> &String {
+        &self.path
+    }
+
+    pub fn get_data(&self) -> &Vec<u8> {
+        &self.data
+    }
+}
+
+/// Resource paths used by the block content
+pub fn get_block_content_paths(block_content: &BlockContent) -> Vec<&String> {
+    block_content
+        .get_resource_paths()
+        .into_iter()
+        .map(|(_, path)| path)
+        .collect()
+}
+
+struct PluginBlock {
+    plugin_slug: String,
+    block_type: BlockType,
+}
+
+/// Host side storage of the plugin blocks and resources.
+#[derive(Default)]
+pub struct PluginBlockRegistry {
+    blocks: BTreeMap<String, PluginBlock>,
+    resources: HashMap<String, Vec<u8>>,
+    /// Resources of the host, like [`DEFAULT_RESOURCES`]
+    known_resources: HashSet<String>,
+}
+
+impl PluginBlockRegistry {
+    /// Plugin blocks may use the [`DEFAULT_RESOURCES`]
+    pub fn new() -> Self {
+        Self::default().known_resources(DEFAULT_RESOURCES.iter().map(|r| r.to_string()))
+    }
+
+    /// Host resources available to the plugin blocks besides their own ones
+    pub fn known_resources(mut self, resources: impl IntoIterator<Item = String>) -> Self {
+        self.known_resources = resources.into_iter().collect();
+        self
+    }
+
+    fn is_known_namespace(&self, namespace: &str) -> bool {
+        self.known_resources
+            .iter()
+            .any(|r| split_resource_path(r).is_some_and(|(n, _)| n == namespace))
+    }
+
+    /// Block must have an explicit slug because its id is stored by the slug.
+    /// The manifest is checked by the [`BlockManifestValidator`], plugin resources are checked in [`Self::validate`].
+    ///
+    /// Resources of the block must belong to the plugin namespace or to the namespaces of the known resources.
+    pub fn register_block(&mut self, plugin_slug: &str, manifest: &BlockTypeManifest) -> Result<(), String> {
+        let Some(slug) = manifest.slug.as_ref() else {
+            return Err(format!("&cplugin &4\"{}\" &cblock must have a slug", plugin_slug));
+        };
+        let re = regex::Regex::new(REGEX_BLOCK_SLUG).unwrap();
+        if !re.is_match(slug) {
+            return Err(format!(
+                "&cblock slug &4\"{}\" &cmust contain only lowercase letters, numbers and \"_\"",
+                slug
+            ));
+        }
+        let diagnostics = BlockManifestValidator::new().validate(std::slice::from_ref(manifest));
+        if !diagnostics.is_empty() {
+            return Err(format_diagnostics(&diagnostics));
+        }
+        if let Some(block) = self.blocks.get(slug) {
+            return Err(format!(
+                "&cblock &4\"{}\" &cis already registered by &4\"{}\"",
+                slug, block.plugin_slug
+            ));
+        }
+        for path in get_block_content_paths(&manifest.block_content) {
+            let namespace = split_resource_path(path).map(|(namespace, _)| namespace);
+            match namespace.as_deref() {
+                Some(n) if n == plugin_slug || self.is_known_namespace(n) => (),
+                _ => {
+                    return Err(format!(
+                        "&cblock &4\"{}\" &cresource &4\"{}\" &cmust be inside &4\"{}://\"",
+                        slug, path, plugin_slug
+                    ))
+                }
+            }
+        }
+        self.blocks.insert(
+            slug.clone(),
+            PluginBlock {
+                plugin_slug: plugin_slug.to_string(),
+                block_type: manifest.to_block()?,
+            },
+        );
+        Ok(())
+    }
+
+    /// Returns the full resource path with the plugin namespace
+    pub fn register_resource(&mut self, plugin_slug: &str, request: RegisterResourceRequest) -> Result<String, String> {
+        let full_path = ResourceUri::create(plugin_slug, request.path)?.to_string();
+        self.resources.insert(full_path.clone(), request.data);
+        Ok(full_path)
+    }
+
+    /// Checks that all resources used by the blocks are registered or known
+    pub fn validate(&self) -> Result<(), String> {
+        for (slug, block) in self.blocks.iter() {
+            for path in get_block_content_paths(block.block_type.get_block_content()) {
+                if !self.known_resources.contains(path) && !self.resources.contains_key(path) {
+                    return Err(format!(
+                        "&cblock &4\"{}\" &cresource &4\"{}\" &cis not registered",
+                        slug, path
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Plugin which registered the block; interaction events are sent only to it
+    pub fn get_block_owner(&self, block_slug: &str) -> Option<&String> {
+        self.blocks.get(block_slug).map(|b| &b.plugin_slug)
+    }
+
+    /// Block types sorted by slug, so new ids don't depend on the plugins load order
+    pub fn iter_block_types(&self) -> impl Iterator<Item = &BlockType> {
+        self.blocks.values().map(|b| &b.block_type)
+    }
+
+    pub fn get_resource(&self, path: &str) -> Option<&Vec<u8>> {
+        self.resources.get(path)
+    }
+
+    pub fn iter_resources(&self) -> impl Iterator<Item = (&String, &Vec<u8>)> {
+        self.resources.iter()
+    }
+
+    /// Adds ids of the default and plugin blocks to the map; stored ids are kept.
+    pub fn generate_block_id_map(
+        &self,
+        block_id_map: &mut BTreeMap<BlockIndexType, String>,
+        default_blocks: &[BlockType],
+    ) -> Result<(), String> {
+        for block in default_blocks.iter() {
+            if let Some(owner) = self.get_block_owner(block.get_slug()) {
+                return Err(format!(
+                    "&cplugin &4\"{}\" &cblock &4\"{}\" &cconflicts with the default block",
+                    owner,
+                    block.get_slug()
+                ));
+            }
+        }
+        generate_block_id_map(block_id_map, default_blocks.iter().chain(self.iter_block_types()))
+    }
+
+    /// Removes the blocks and resources of the unloaded plugin
+    pub fn remove_plugin(&mut self, plugin_slug: &str) {
+        self.blocks.retain(|_, b| b.plugin_slug != plugin_slug);
+        let prefix = format!("{}://", plugin_slug);
+        self.resources.retain(|path, _| !path.starts_with(&prefix));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{PluginBlockRegistry, RegisterResourceRequest};
+    use crate::{
+        blocks::{block_shape::BlockShape, block_type::BlockTypeManifest},
+        default_blocks::generate_default_blocks,
+        default_blocks_ids::CUSTOM_BLOCK_ID_START,
+    };
+
+    fn manifest(slug: &str, texture: &str) -> BlockTypeManifest {
+        let yaml = format!("slug: {}\nblock_content: !texture\n  texture: \"{}\"", slug, texture);
+        serde_yaml::from_str(&yaml).unwrap()
+    }
+
+    #[test]
+    fn test_plugin_blocks_register() {
+        let mut registry = PluginBlockRegistry::new();
+        registry
+            .register_block("gems", &manifest("ruby_block", "gems://blocks/ruby.png"))
+            .unwrap();
+        assert!(registry
+            .register_block("other", &manifest("ruby_block", "other://ruby.png"))
+            .is_err());
+        assert!(registry
+            .register_block("other", &manifest("emerald_block", "gems://blocks/ruby.png"))
+            .is_err());
+        assert!(registry.validate().is_err());
+
+        let path = registry
+            .register_resource("gems", RegisterResourceRequest::create("/blocks/ruby.png", vec![1, 2]))
+            .unwrap();
+        assert_eq!(path, "gems://blocks/ruby.png");
+        for invalid in ["../ruby.png", "./ruby.png", "blocks//ruby.png"] {
+            assert!(registry
+                .register_resource("gems", RegisterResourceRequest::create(invalid, vec![]))
+                .is_err());
+        }
+        assert!(registry
+            .register_block("gems", &manifest("sapphire_block", "gems://blocks//sapphire.png"))
+            .is_err());
+        // Explicit slug is not generated from the path
+        registry
+            .register_block("gems", &manifest("topaz_block", "gems://blocks/topaz-block.png"))
+            .unwrap();
+        registry
+            .register_resource(
+                "gems",
+                RegisterResourceRequest::create("blocks/topaz-block.png", vec![3]),
+            )
+            .unwrap();
+        assert!(registry.validate().is_ok());
+        assert_eq!(registry.get_block_owner("ruby_block").unwrap(), "gems");
+
+        // Invalid shape and collider are rejected
+        let mut invalid = manifest("glass_block", "gems://blocks/glass.png");
+        invalid.block_content = invalid.block_content.shape(BlockShape::Boxes(vec![]));
+        assert!(registry.register_block("gems", &invalid).is_err());
+        let yaml = "slug: glass_block\nblock_content: !texture\n  texture: gems://blocks/glass.png\ncollider:\n  friction: .nan";
+        let invalid: BlockTypeManifest = serde_yaml::from_str(yaml).unwrap();
+        assert!(registry.register_block("gems", &invalid).is_err());
+
+        // Namespaces of the known resources are allowed
+        registry
+            .register_block("gems", &manifest("rose_bush", "foliage://flower_rose.glb"))
+            .unwrap();
+        assert!(registry.validate().is_ok());
+        assert!(registry
+            .register_block("gems", &manifest("moss_block", "ores://moss.png"))
+            .is_err());
+
+        registry.remove_plugin("gems");
+        assert!(registry.get_block_owner("ruby_block").is_none());
+        assert!(registry.get_resource(&path).is_none());
+    }
+
+    #[test]
+    fn test_plugin_blocks_ids() {
+        let default_blocks = generate_default_blocks().unwrap();
+        let mut registry = PluginBlockRegistry::new();
+        registry
+            .register_block("gems", &manifest("ruby_block", "gems://ruby.png"))
+            .unwrap();
+        registry
+            .register_block("ores", &manifest("copper_ore", "ores://copper.png"))
+            .unwrap();
+
+        let mut block_id_map = BTreeMap::new();
+        registry
+            .generate_block_id_map(&mut block_id_map, &default_blocks)
+            .unwrap();
+        let copper_id = *block_id_map.iter().find(|(_, s)| *s == "copper_ore").unwrap().0;
+        assert!(copper_id > CUSTOM_BLOCK_ID_START);
+
+        // Stored ids are kept when another plugin adds blocks
+        registry
+            .register_block("alloys", &manifest("bronze_block", "alloys://bronze.png"))
+            .unwrap();
+        registry
+            .generate_block_id_map(&mut block_id_map, &default_blocks)
+            .unwrap();
+        assert_eq!(block_id_map.get(&copper_id).unwrap(), "copper_ore");
+
+        let slug = default_blocks[0].get_slug().clone();
+        registry
+            .register_block("gems", &manifest(&slug, "default://assets/block/dirt.png"))
+            .unwrap();
+        assert!(registry
+            .generate_block_id_map(&mut block_id_map, &default_blocks)
+            .is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+use crate::chunks::{block_position::BlockPosition, chunk_data::BlockDataInfo};
+
+use super::{outcome::CancellableEvent, player_info::PlayerInfo, PluginEvent};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockInteraction {
+    /// Right click
+    Use,
+    /// Left click
+    Attack,
+}
+
+/// Sent only to the plugin which registered the block type.
+///
+/// Cancel prevents the default behaviour of the interaction.
+#[derive(Serialize, Deserialize)]
+pub struct BlockInteractEvent {
+    player: PlayerInfo,
+    world_slug: String,
+    position: BlockPosition,
+    block: BlockDataInfo,
+    block_slug: String,
+    interaction: BlockInteraction,
+}
+
+impl PluginEvent for BlockInteractEvent {
+    const EXPORT_NAME: &'static str = "on_block_interact";
+}
+
+impl CancellableEvent for BlockInteractEvent {}
+
+impl BlockInteractEvent {
+    pub fn create(
+        player: PlayerInfo,
+        world_slug: impl Into<String>,
+        position: BlockPosition,
+        block: BlockDataInfo,
+        block_slug: impl Into<String>,
+        interaction: BlockInteraction,
+    ) -> Self {
+        Self {
+            player,
+            world_slug: world_slug.into(),
+            position,
+            block,
+            block_slug: block_slug.into(),
+            interaction,
+        }
+    }
+
+    pub fn get_player(&self) -> &PlayerInfo {
+        &self.player
+    }
+
+    pub fn get_world_slug(&self) -> &String {
+        &self.world_slug
+    }
+
+    pub fn get_position(&self) -> &BlockPosition {
+        &self.position
+    }
+
+    pub fn get_block(&self) -> &BlockDataInfo {
+        &self.block
+    }
+
+    pub fn get_block_slug(&self) -> &String {
+        &self.block_slug
+    }
+
+    pub fn get_interaction(&self) -> BlockInteraction {
+        self.interaction
+    }
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+use crate::chunks::{block_position::BlockPosition, chunk_data::BlockDataInfo};
+
+use super::{outcome::CancellableEvent, player_info::PlayerInfo, PluginEvent};
+
+#[derive(Serialize, Deserialize)]
+pub struct BlockPlaceEvent {
+    player: PlayerInfo,
+    world_slug: String,
+    position: BlockPosition,
+    block: BlockDataInfo,
+}
+
+impl PluginEvent for BlockPlaceEvent {
+    const EXPORT_NAME: &'static str = "on_block_place";
+}
+
+impl CancellableEvent for BlockPlaceEvent {}
+
+impl BlockPlaceEvent {
+    pub fn create(
+        player: PlayerInfo,
+        world_slug: impl Into<String>,
+        position: BlockPosition,
+        block: BlockDataInfo,
+    ) -> Self {
+        Self {
+            player,
+            world_slug: world_slug.into(),
+            position,
+            block,
+        }
+    }
+
+    pub fn get_player(&self) -> &PlayerInfo {
+        &self.player
+    }
+
+    pub fn get_world_slug(&self) -> &String {
+        &self.world_slug
+    }
+
+    pub fn get_position(&self) -> &BlockPosition {
+        &self.position
+    }
+
+    /// Block which is going to be placed
+    pub fn get_block(&self) -> &BlockDataInfo {
+        &self.block
+    }
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+use super::{outcome::CancellableEvent, player_info::PlayerInfo, PluginEvent};
+
+/// Cancelled message isn't sent to anyone
+#[derive(Serialize, Deserialize)]
+pub struct ChatMessageEvent {
+    player: PlayerInfo,
+    message: String,
+}
+
+impl PluginEvent for ChatMessageEvent {
+    const EXPORT_NAME: &'static str = "on_chat_message";
+}
+
+impl CancellableEvent for ChatMessageEvent {}
+
+impl ChatMessageEvent {
+    pub fn create(player: PlayerInfo, message: impl Into<String>) -> Self {
+        Self {
+            player,
+            message: message.into(),
+        }
+    }
+
+    pub fn get_player(&self) -> &PlayerInfo {
+        &self.player
+    }
+
+    pub fn get_message(&self) -> &String {
+        &self.message
+    }
+}
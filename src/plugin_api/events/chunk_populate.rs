@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    chunks::{
+        block_position::{BlockPosition, BlockPositionTrait},
+        chunk_data::{BlockDataInfo, ChunkData},
+        chunk_delta::ChunkDelta,
+        chunk_position::ChunkPosition,
+    },
+    world_generator::traits::WorldGeneratorSettings,
+};
+
+use super::PluginEvent;
+
+/// Sent after the chunk terrain is generated, so plugins can decorate it
+/// (ores, structures, ruins) without generating the whole chunk.
+///
+/// Plugins are called one after another by priority; each one receives
+/// the chunk with the changes of the previous plugins.
+#[derive(Serialize, Deserialize)]
+pub struct ChunkPopulateEvent {
+    chunk_position: ChunkPosition,
+    world_settings: WorldGeneratorSettings,
+    chunk_data: ChunkData,
+
+    /// Generated neighbouring chunks, read only
+    #[serde(default)]
+    neighbours: Vec<(ChunkPosition, ChunkData)>,
+}
+
+impl PluginEvent for ChunkPopulateEvent {
+    const EXPORT_NAME: &'static str = "on_chunk_populate";
+}
+
+impl ChunkPopulateEvent {
+    pub fn create(
+        chunk_position: ChunkPosition,
+        world_settings: WorldGeneratorSettings,
+        chunk_data: ChunkData,
+        neighbours: Vec<(ChunkPosition, ChunkData)>,
+    ) -> Self {
+        Self {
+            chunk_position,
+            world_settings,
+            chunk_data,
+            neighbours,
+        }
+    }
+
+    pub fn get_chunk_position(&self) -> &ChunkPosition {
+        &self.chunk_position
+    }
+
+    pub fn get_world_settings(&self) -> &WorldGeneratorSettings {
+        &self.world_settings
+    }
+
+    pub fn get_chunk_data(&self) -> &ChunkData {
+        &self.chunk_data
+    }
+
+    pub fn get_chunk_data_mut(&mut self) -> &mut ChunkData {
+        &mut self.chunk_data
+    }
+
+    pub fn take_chunk_data(self) -> ChunkData {
+        self.chunk_data
+    }
+
+    pub fn get_neighbour(&self, chunk_position: &ChunkPosition) -> Option<&ChunkData> {
+        self.neighbours
+            .iter()
+            .find(|(position, _)| position == chunk_position)
+            .map(|(_, chunk_data)| chunk_data)
+    }
+
+    /// Reads the block from the chunk or its neighbours; `None` for air and unknown chunks
+    pub fn get_block(&self, position: &BlockPosition) -> Option<BlockDataInfo> {
+        let chunk_position = position.get_chunk_position();
+        let chunk_data = match chunk_position == self.chunk_position {
+            true => &self.chunk_data,
+            false => self.get_neighbour(&chunk_position)?,
+        };
+        if position.get_y() < 0 {
+            return None;
+        }
+        let (section, block_position) = position.get_block_position();
+        chunk_data.get(section as usize)?.get(&block_position).copied()
+    }
+}
+
+/// Result of the `on_chunk_populate` handler
+#[derive(Serialize, Deserialize, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChunkPopulateResult {
+    #[default]
+    Unchanged,
+    /// Replaces the whole chunk
+    Replace { chunk_data: ChunkData },
+    /// Changes outside of the chunk are applied to the neighbours by the host
+    Delta { delta: ChunkDelta },
+}
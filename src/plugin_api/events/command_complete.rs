@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+use super::{command_executed::CommandSender, PluginEvent};
+
+/// Requests choices of the [`ArgType::Dynamic`](crate::commands::command::ArgType::Dynamic) argument
+/// from the plugin which registered the command.
+///
+/// Handler returns `Vec<String>` with all choices; the host filters them by the entered text.
+#[derive(Serialize, Deserialize)]
+pub struct CommandCompleteEvent {
+    sender: CommandSender,
+    line: String,
+    command: String,
+    #[serde(default)]
+    subcommands: Vec<String>,
+    arg: String,
+}
+
+impl PluginEvent for CommandCompleteEvent {
+    const EXPORT_NAME: &'static str = "on_command_complete";
+}
+
+impl CommandCompleteEvent {
+    pub fn create(
+        sender: CommandSender,
+        line: impl Into<String>,
+        command: impl Into<String>,
+        arg: impl Into<String>,
+    ) -> Self {
+        Self {
+            sender,
+            line: line.into(),
+            command: command.into(),
+            subcommands: Default::default(),
+            arg: arg.into(),
+        }
+    }
+
+    pub fn subcommands(mut self, subcommands: Vec<String>) -> Self {
+        self.subcommands = subcommands;
+        self
+    }
+
+    pub fn get_sender(&self) -> &CommandSender {
+        &self.sender
+    }
+
+    /// Command line up to the cursor
+    pub fn get_line(&self) -> &String {
+        &self.line
+    }
+
+    /// Name of the registered root command
+    pub fn get_command(&self) -> &String {
+        &self.command
+    }
+
+    /// Subcommands between the root command and the argument, like ["go"] for "home go <name>"
+    pub fn get_subcommands(&self) -> &Vec<String> {
+        &self.subcommands
+    }
+
+    pub fn get_arg(&self) -> &String {
+        &self.arg
+    }
+}
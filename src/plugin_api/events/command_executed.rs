@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+use crate::commands::command::CommandMatch;
+
+use super::{outcome::CancellableEvent, player_info::PlayerInfo, PluginEvent};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandSender {
+    Console,
+    Player(PlayerInfo),
+}
+
+/// Sent before the command is executed; cancelled command is ignored.
+///
+/// The plugin which registered the command receives the event
+/// with the parsed [`CommandMatch`] and must execute it.
+#[derive(Serialize, Deserialize)]
+pub struct CommandExecutedEvent {
+    sender: CommandSender,
+    command: String,
+    #[serde(default)]
+    command_match: Option<CommandMatch>,
+}
+
+impl PluginEvent for CommandExecutedEvent {
+    const EXPORT_NAME: &'static str = "on_command_executed";
+}
+
+impl CancellableEvent for CommandExecutedEvent {}
+
+impl CommandExecutedEvent {
+    pub fn create(sender: CommandSender, command: impl Into<String>) -> Self {
+        Self {
+            sender,
+            command: command.into(),
+            command_match: None,
+        }
+    }
+
+    pub fn command_match(mut self, command_match: CommandMatch) -> Self {
+        self.command_match = Some(command_match);
+        self
+    }
+
+    pub fn get_sender(&self) -> &CommandSender {
+        &self.sender
+    }
+
+    /// Command line as it was entered
+    pub fn get_command(&self) -> &String {
+        &self.command
+    }
+
+    /// Present only for the plugin which registered the command
+    pub fn get_command_match(&self) -> Option<&CommandMatch> {
+        self.command_match.as_ref()
+    }
+}
@@ -1,9 +1,27 @@
-#[cfg(feature = "wasm-plugin")]
 pub mod plugin_load;
 pub mod plugin_unload;
 pub mod generage_chunk;
 pub mod generage_world_macro;
+pub mod chunk_populate;
 
+pub mod outcome;
+pub mod player_info;
+pub mod player_join;
+pub mod player_leave;
+pub mod player_move;
+pub mod block_place;
+pub mod block_break;
+pub mod block_interact;
+pub mod chat_message;
+pub mod command_complete;
+pub mod command_executed;
+pub mod plugin_message;
+pub mod scheduled_task;
+
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a plugin event",
+    note = "event handler argument must implement `PluginEvent`"
+)]
 pub trait PluginEvent: Sized {
     const EXPORT_NAME: &'static str;
 }
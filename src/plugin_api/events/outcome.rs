@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+use super::PluginEvent;
+use crate::plugin_api::handler_result::parse_handler_output;
+
+/// Event which can be vetoed by a plugin.
+///
+/// Handlers return [`EventOutcome`] and the host cancels the action
+/// if any of the handlers returned [`EventOutcome::Cancel`].
+pub trait CancellableEvent: PluginEvent {}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventOutcome {
+    #[default]
+    Continue,
+    Cancel,
+}
+
+impl EventOutcome {
+    pub fn is_cancelled(&self) -> bool {
+        *self == EventOutcome::Cancel
+    }
+
+    /// Parses the handler output; handlers without return value continue the action.
+    pub fn from_output(output: &str) -> Result<Self, String> {
+        let outcome: Option<EventOutcome> =
+            parse_handler_output(output).map_err(|e| format!("Event outcome &e\"{}\"&r is invalid: {}", output, e))?;
+        Ok(outcome.unwrap_or_default())
+    }
+
+    /// Combines outcomes of several handlers; one cancel is enough
+    pub fn merge(self, other: EventOutcome) -> Self {
+        match self.is_cancelled() || other.is_cancelled() {
+            true => EventOutcome::Cancel,
+            false => EventOutcome::Continue,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EventOutcome;
+
+    #[test]
+    fn test_event_outcome_from_output() {
+        assert_eq!(EventOutcome::from_output("").unwrap(), EventOutcome::Continue);
+        assert_eq!(EventOutcome::from_output("\"cancel\"").unwrap(), EventOutcome::Cancel);
+        assert_eq!(
+            EventOutcome::from_output(&serde_json::to_string(&EventOutcome::Continue).unwrap()).unwrap(),
+            EventOutcome::Continue
+        );
+        assert_eq!(
+            EventOutcome::from_output(r#"{"status":"ok","value":null}"#).unwrap(),
+            EventOutcome::Continue
+        );
+        assert!(EventOutcome::from_output(r#"{"status":"err","kind":"handler","message":"error"}"#).is_err());
+        assert!(EventOutcome::from_output("\"maybe\"").is_err());
+    }
+
+    #[test]
+    fn test_event_outcome_merge() {
+        let outcome = [EventOutcome::Continue, EventOutcome::Cancel, EventOutcome::Continue]
+            .into_iter()
+            .fold(EventOutcome::default(), EventOutcome::merge);
+        assert!(outcome.is_cancelled());
+    }
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// Player who caused the event
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PlayerInfo {
+    client_id: u64,
+    login: String,
+}
+
+impl PlayerInfo {
+    pub fn create(client_id: u64, login: impl Into<String>) -> Self {
+        Self {
+            client_id,
+            login: login.into(),
+        }
+    }
+
+    pub fn get_client_id(&self) -> u64 {
+        self.client_id
+    }
+
+    pub fn get_login(&self) -> &String {
+        &self.login
+    }
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+use super::{player_info::PlayerInfo, PluginEvent};
+
+#[derive(Serialize, Deserialize)]
+pub struct PlayerJoinEvent {
+    player: PlayerInfo,
+}
+
+impl PluginEvent for PlayerJoinEvent {
+    const EXPORT_NAME: &'static str = "on_player_join";
+}
+
+impl PlayerJoinEvent {
+    pub fn create(player: PlayerInfo) -> Self {
+        Self { player }
+    }
+
+    pub fn get_player(&self) -> &PlayerInfo {
+        &self.player
+    }
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+use crate::chunks::{position::Vector3, rotation::Rotation};
+
+use super::{outcome::CancellableEvent, player_info::PlayerInfo, PluginEvent};
+
+/// Cancelled movement returns the player to the `from` position
+#[derive(Serialize, Deserialize)]
+pub struct PlayerMoveEvent {
+    player: PlayerInfo,
+    world_slug: String,
+    from: Vector3,
+    to: Vector3,
+    rotation: Rotation,
+}
+
+impl PluginEvent for PlayerMoveEvent {
+    const EXPORT_NAME: &'static str = "on_player_move";
+}
+
+impl CancellableEvent for PlayerMoveEvent {}
+
+impl PlayerMoveEvent {
+    pub fn create(
+        player: PlayerInfo,
+        world_slug: impl Into<String>,
+        from: Vector3,
+        to: Vector3,
+        rotation: Rotation,
+    ) -> Self {
+        Self {
+            player,
+            world_slug: world_slug.into(),
+            from,
+            to,
+            rotation,
+        }
+    }
+
+    pub fn get_player(&self) -> &PlayerInfo {
+        &self.player
+    }
+
+    pub fn get_world_slug(&self) -> &String {
+        &self.world_slug
+    }
+
+    pub fn get_from(&self) -> &Vector3 {
+        &self.from
+    }
+
+    pub fn get_to(&self) -> &Vector3 {
+        &self.to
+    }
+
+    pub fn get_rotation(&self) -> &Rotation {
+        &self.rotation
+    }
+}
@@ -1,25 +1,86 @@
 use super::PluginEvent;
-use serde::{Deserialize, Serialize};
+use crate::plugin_api::plugin_state::PluginState;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
-pub struct PluginLoadEvent {}
+#[derive(Serialize, Deserialize, Default)]
+pub struct PluginLoadEvent {
+    /// State of the previous instance if the plugin was reloaded
+    #[serde(default)]
+    previous_state: Option<PluginState>,
+}
 
 impl PluginEvent for PluginLoadEvent {
     const EXPORT_NAME: &'static str = "on_plugin_load";
 }
 
-#[extism_pdk::host_fn]
-extern "ExtismHost" {
-    fn register_world_generator_raw(name: String) -> ();
-    fn get_plugin_slug_raw() -> String;
+impl PluginLoadEvent {
+    pub fn create(previous_state: Option<PluginState>) -> Self {
+        Self { previous_state }
+    }
+
+    pub fn get_previous_state(&self) -> Option<&PluginState> {
+        self.previous_state.as_ref()
+    }
+
+    /// Returns `None` if there is no state or it was saved with another version
+    pub fn get_previous_state_as<T: DeserializeOwned>(&self, version: u32) -> Result<Option<T>, String> {
+        match self.previous_state.as_ref() {
+            Some(state) if state.get_version() == version => Ok(Some(state.get_data_as()?)),
+            _ => Ok(None),
+        }
+    }
 }
 
-impl PluginLoadEvent {
-    pub fn register_world_generator(&self, name: &str) -> Result<(), extism_pdk::Error> {
-        unsafe { register_world_generator_raw(name.to_string()) }
+#[cfg(feature = "wasm-plugin")]
+mod plugin {
+    use super::PluginLoadEvent;
+    use crate::{
+        blocks::block_type::BlockTypeManifest, commands::command::Command,
+        plugin_api::custom_blocks::RegisterResourceRequest, utils::compressable::Compressable,
+    };
+
+    #[cfg(not(feature = "mock-host"))]
+    #[extism_pdk::host_fn]
+    extern "ExtismHost" {
+        fn register_world_generator_raw(name: String) -> ();
+        fn get_plugin_slug_raw() -> String;
+        fn register_command_raw(command: String) -> ();
+        fn register_block_raw(manifest: String) -> ();
+        fn register_resource_raw(request: Vec<u8>) -> ();
     }
 
-    pub fn get_slug(&self) -> Result<String, extism_pdk::Error> {
-        unsafe { get_plugin_slug_raw() }
+    #[cfg(feature = "mock-host")]
+    use crate::plugin_api::mock_host::host::{
+        get_plugin_slug_raw, register_block_raw, register_command_raw, register_resource_raw,
+        register_world_generator_raw,
+    };
+
+    impl PluginLoadEvent {
+        pub fn register_world_generator(&self, name: &str) -> Result<(), extism_pdk::Error> {
+            unsafe { register_world_generator_raw(name.to_string()) }
+        }
+
+        /// Executions of the command are sent to this plugin as
+        /// [`CommandExecutedEvent`](crate::plugin_api::events::command_executed::CommandExecutedEvent),
+        /// [`ArgType::Dynamic`](crate::commands::command::ArgType::Dynamic) choices are requested with
+        /// [`CommandCompleteEvent`](crate::plugin_api::events::command_complete::CommandCompleteEvent).
+        pub fn register_command(&self, command: &Command) -> Result<(), extism_pdk::Error> {
+            unsafe { register_command_raw(serde_json::to_string(command)?) }
+        }
+
+        /// Block gets a stable custom id by its slug; interactions with it are sent to this plugin as
+        /// [`BlockInteractEvent`](crate::plugin_api::events::block_interact::BlockInteractEvent).
+        pub fn register_block(&self, manifest: &BlockTypeManifest) -> Result<(), extism_pdk::Error> {
+            unsafe { register_block_raw(serde_json::to_string(manifest)?) }
+        }
+
+        /// Resource is available as "<plugin slug>://<path>" for the block manifests
+        pub fn register_resource(&self, path: &str, data: Vec<u8>) -> Result<(), extism_pdk::Error> {
+            unsafe { register_resource_raw(RegisterResourceRequest::create(path, data).encode()) }
+        }
+
+        pub fn get_slug(&self) -> Result<String, extism_pdk::Error> {
+            unsafe { get_plugin_slug_raw() }
+        }
     }
 }
@@ -0,0 +1,80 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::PluginEvent;
+
+/// Message published on the channel the plugin subscribed to
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PluginMessageEvent {
+    sender: String,
+    channel: String,
+    payload: serde_json::Value,
+}
+
+impl PluginEvent for PluginMessageEvent {
+    const EXPORT_NAME: &'static str = "on_plugin_message";
+}
+
+impl PluginMessageEvent {
+    pub fn create(sender: impl Into<String>, channel: impl Into<String>, payload: serde_json::Value) -> Self {
+        Self {
+            sender: sender.into(),
+            channel: channel.into(),
+            payload,
+        }
+    }
+
+    /// Slug of the plugin which published the message
+    pub fn get_sender(&self) -> &String {
+        &self.sender
+    }
+
+    pub fn get_channel(&self) -> &String {
+        &self.channel
+    }
+
+    pub fn get_payload(&self) -> &serde_json::Value {
+        &self.payload
+    }
+
+    pub fn get_payload_as<T: DeserializeOwned>(&self) -> Result<T, String> {
+        serde_json::from_value(self.payload.clone()).map_err(|e| format!("Message payload error: {}", e))
+    }
+}
+
+/// Request from another plugin; the handler return value is sent back as the response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PluginRequestEvent {
+    sender: String,
+    channel: String,
+    payload: serde_json::Value,
+}
+
+impl PluginEvent for PluginRequestEvent {
+    const EXPORT_NAME: &'static str = "on_plugin_request";
+}
+
+impl PluginRequestEvent {
+    pub fn create(sender: impl Into<String>, channel: impl Into<String>, payload: serde_json::Value) -> Self {
+        Self {
+            sender: sender.into(),
+            channel: channel.into(),
+            payload,
+        }
+    }
+
+    pub fn get_sender(&self) -> &String {
+        &self.sender
+    }
+
+    pub fn get_channel(&self) -> &String {
+        &self.channel
+    }
+
+    pub fn get_payload(&self) -> &serde_json::Value {
+        &self.payload
+    }
+
+    pub fn get_payload_as<T: DeserializeOwned>(&self) -> Result<T, String> {
+        serde_json::from_value(self.payload.clone()).map_err(|e| format!("Request payload error: {}", e))
+    }
+}
@@ -2,9 +2,37 @@ use serde::{Deserialize, Serialize};
 
 use super::PluginEvent;
 
-#[derive(Serialize, Deserialize)]
-pub struct PluginUnloadEvent {}
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UnloadReason {
+    #[default]
+    Shutdown,
+    /// New instance of the plugin will be loaded with the returned state
+    Reload,
+}
+
+/// Handler may return [`PluginState`](crate::plugin_api::plugin_state::PluginState)
+/// to hand it over to the new instance on reload.
+#[derive(Serialize, Deserialize, Default)]
+pub struct PluginUnloadEvent {
+    #[serde(default)]
+    reason: UnloadReason,
+}
 
 impl PluginEvent for PluginUnloadEvent {
     const EXPORT_NAME: &'static str = "on_plugin_unload";
 }
+
+impl PluginUnloadEvent {
+    pub fn create(reason: UnloadReason) -> Self {
+        Self { reason }
+    }
+
+    pub fn get_reason(&self) -> UnloadReason {
+        self.reason
+    }
+
+    pub fn is_reload(&self) -> bool {
+        self.reason == UnloadReason::Reload
+    }
+}
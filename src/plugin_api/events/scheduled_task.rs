@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+use super::PluginEvent;
+
+/// Sent to the plugin which scheduled the task when it is due
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ScheduledTaskEvent {
+    task_id: u64,
+    tick: u64,
+    payload: serde_json::Value,
+}
+
+impl PluginEvent for ScheduledTaskEvent {
+    const EXPORT_NAME: &'static str = "on_scheduled_task";
+}
+
+impl ScheduledTaskEvent {
+    pub fn create(task_id: u64, tick: u64, payload: serde_json::Value) -> Self {
+        Self { task_id, tick, payload }
+    }
+
+    pub fn get_task_id(&self) -> u64 {
+        self.task_id
+    }
+
+    /// Server tick on which the task was run
+    pub fn get_tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn get_payload(&self) -> &serde_json::Value {
+        &self.payload
+    }
+
+    pub fn get_payload_as<T: serde::de::DeserializeOwned>(&self) -> Result<T, String> {
+        serde_json::from_value(self.payload.clone()).map_err(|e| format!("Task payload error: {}", e))
+    }
+}
@@ -0,0 +1,171 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// Stage of the event handler call which failed
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HandlerErrorKind {
+    /// Plugin couldn't read the input
+    Input,
+    /// Event payload doesn't match the event type
+    Deserialize,
+    /// Handler returned an error
+    Handler,
+    /// Handler return value couldn't be serialized
+    Serialize,
+    /// Host couldn't parse the plugin output
+    Output,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandlerError {
+    kind: HandlerErrorKind,
+    message: String,
+}
+
+impl HandlerError {
+    pub fn create(kind: HandlerErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    pub fn get_kind(&self) -> HandlerErrorKind {
+        self.kind
+    }
+
+    pub fn get_message(&self) -> &String {
+        &self.message
+    }
+}
+
+impl Display for HandlerError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{:?} error: {}", self.kind, self.message)
+    }
+}
+
+/// Output of every [`event_handler`](crate::event_handler) call.
+///
+/// Handlers without return value produce `Ok` with `null` value.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum HandlerResult {
+    Ok { value: serde_json::Value },
+    Err { kind: HandlerErrorKind, message: String },
+}
+
+impl HandlerResult {
+    pub fn ok(value: serde_json::Value) -> Self {
+        Self::Ok { value }
+    }
+
+    pub fn err(kind: HandlerErrorKind, message: impl Into<String>) -> Self {
+        Self::Err {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// Parses the plugin output on the host side.
+    ///
+    /// Empty output and json without the envelope are accepted as `Ok`
+    /// for plugins built before the envelope was introduced. Only an object with
+    /// exactly the envelope keys is the envelope, so `{"status": "online"}` is a value.
+    pub fn parse(output: &str) -> Result<Self, HandlerError> {
+        if output.trim().is_empty() {
+            return Ok(Self::ok(serde_json::Value::Null));
+        }
+        let value: serde_json::Value = serde_json::from_str(output)
+            .map_err(|e| HandlerError::create(HandlerErrorKind::Output, format!("invalid json: {}", e)))?;
+
+        if !Self::is_envelope(&value) {
+            return Ok(Self::ok(value));
+        }
+        serde_json::from_value(value).map_err(|e| HandlerError::create(HandlerErrorKind::Output, e.to_string()))
+    }
+
+    fn is_envelope(value: &serde_json::Value) -> bool {
+        let Some(object) = value.as_object() else {
+            return false;
+        };
+        let keys: &[&str] = match object.get("status").and_then(|s| s.as_str()) {
+            Some("ok") => &["status", "value"],
+            Some("err") => &["status", "kind", "message"],
+            _ => return false,
+        };
+        object.len() == keys.len() && keys.iter().all(|k| object.contains_key(*k))
+    }
+
+    /// Converts the result into the handler return value
+    pub fn into_value<T: DeserializeOwned>(self) -> Result<T, HandlerError> {
+        match self {
+            Self::Ok { value } => serde_json::from_value(value)
+                .map_err(|e| HandlerError::create(HandlerErrorKind::Output, format!("unexpected value: {}", e))),
+            Self::Err { kind, message } => Err(HandlerError::create(kind, message)),
+        }
+    }
+
+    /// Writes the result as the plugin output and returns the exit code of the export.
+    ///
+    /// The code is 0 whenever the output was written: the host doesn't read the output
+    /// of failed calls, so errors are reported by the `status` field.
+    #[cfg(feature = "wasm-plugin")]
+    pub fn output(self) -> i32 {
+        if let Self::Err { kind, message } = &self {
+            extism_pdk::log!(extism_pdk::LogLevel::Error, "Event {:?} error: {}", kind, message);
+        }
+        let output = serde_json::to_string(&self).expect("Failed to serialize handler result");
+        match extism_pdk::output(&output) {
+            Ok(()) => 0,
+            Err(e) => {
+                extism_pdk::log!(extism_pdk::LogLevel::Error, "Output error: {:?}", e);
+                1
+            }
+        }
+    }
+}
+
+/// Parses the plugin output into the handler return value
+pub fn parse_handler_output<T: DeserializeOwned>(output: &str) -> Result<T, HandlerError> {
+    HandlerResult::parse(output)?.into_value()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_handler_output, HandlerErrorKind, HandlerResult};
+
+    #[test]
+    fn test_handler_result() {
+        let output = serde_json::to_string(&HandlerResult::ok(serde_json::json!(["a", "b"]))).unwrap();
+        let value: Vec<String> = parse_handler_output(&output).unwrap();
+        assert_eq!(value, vec!["a".to_string(), "b".to_string()]);
+
+        let output = serde_json::to_string(&HandlerResult::err(HandlerErrorKind::Handler, "no world")).unwrap();
+        let error = parse_handler_output::<()>(&output).err().unwrap();
+        assert_eq!(error.get_kind(), HandlerErrorKind::Handler);
+        assert_eq!(error.get_message(), "no world");
+    }
+
+    #[test]
+    fn test_handler_result_legacy() {
+        assert_eq!(parse_handler_output::<Option<u32>>("").unwrap(), None);
+        assert_eq!(parse_handler_output::<u32>("5").unwrap(), 5);
+
+        let error = parse_handler_output::<u32>("\"text\"").err().unwrap();
+        assert_eq!(error.get_kind(), HandlerErrorKind::Output);
+        let error = parse_handler_output::<u32>("{").err().unwrap();
+        assert_eq!(error.get_kind(), HandlerErrorKind::Output);
+
+        // Objects with the "status" key which are not the envelope
+        for legacy in [
+            r#"{"status":"online","players":3}"#,
+            r#"{"status":"ok","players":3}"#,
+            r#"{"status":"err","message":"offline"}"#,
+        ] {
+            let value: serde_json::Value = parse_handler_output(legacy).unwrap();
+            assert_eq!(value, serde_json::from_str::<serde_json::Value>(legacy).unwrap());
+        }
+    }
+}
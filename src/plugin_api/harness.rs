@@ -0,0 +1,110 @@
+use serde::de::DeserializeOwned;
+
+use super::{
+    handler_result::{HandlerError, HandlerErrorKind},
+    native::{DispatchEvent, Plugin},
+    registry::PluginRegistry,
+};
+
+/// Runs a single native plugin in tests.
+///
+/// Events are sent through json like to the WASM plugins, so a plugin
+/// which passes the harness receives the same data on the server.
+pub struct PluginHarness {
+    slug: String,
+    registry: PluginRegistry,
+    history: Vec<String>,
+}
+
+impl PluginHarness {
+    /// Registers the plugin and calls its `on_plugin_load`
+    pub fn create(plugin: impl Plugin + 'static) -> Result<Self, String> {
+        let slug = plugin.get_slug().to_string();
+        let mut registry = PluginRegistry::new();
+        registry.register_native(Box::new(plugin))?;
+        Ok(Self {
+            slug,
+            registry,
+            history: Vec::new(),
+        })
+    }
+
+    pub fn send<E: DispatchEvent + DeserializeOwned>(&mut self, event: &E) -> Result<E::Output, HandlerError> {
+        self.history.push(E::EXPORT_NAME.to_string());
+
+        let input = serde_json::to_string(event)
+            .map_err(|e| HandlerError::create(HandlerErrorKind::Serialize, e.to_string()))?;
+        let event: E = serde_json::from_str(&input)
+            .map_err(|e| HandlerError::create(HandlerErrorKind::Deserialize, e.to_string()))?;
+
+        let output = self
+            .registry
+            .dispatch_to(&self.slug, &event)
+            .expect("harness plugin must be registered")?;
+
+        let output = serde_json::to_string(&output)
+            .map_err(|e| HandlerError::create(HandlerErrorKind::Serialize, e.to_string()))?;
+        serde_json::from_str(&output).map_err(|e| HandlerError::create(HandlerErrorKind::Output, e.to_string()))
+    }
+
+    /// Export names of the sent events
+    pub fn get_history(&self) -> &Vec<String> {
+        &self.history
+    }
+
+    /// Sends the unload event and drops the plugin
+    pub fn unload(mut self) -> Result<(), HandlerError> {
+        self.registry.unregister(&self.slug).unwrap_or(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PluginHarness;
+    use crate::plugin_api::{
+        events::{
+            command_complete::CommandCompleteEvent, command_executed::CommandSender, player_info::PlayerInfo,
+            player_join::PlayerJoinEvent,
+        },
+        native::Plugin,
+    };
+
+    #[derive(Default)]
+    struct Greeter {
+        joined: Vec<String>,
+    }
+
+    impl Plugin for Greeter {
+        fn get_slug(&self) -> &str {
+            "greeter"
+        }
+
+        fn on_player_join(&mut self, event: &PlayerJoinEvent) -> Result<(), String> {
+            if event.get_player().get_login().is_empty() {
+                return Err("empty login".to_string());
+            }
+            self.joined.push(event.get_player().get_login().clone());
+            Ok(())
+        }
+
+        fn on_command_complete(&mut self, _event: &CommandCompleteEvent) -> Result<Vec<String>, String> {
+            Ok(self.joined.clone())
+        }
+    }
+
+    #[test]
+    fn test_harness() {
+        let mut harness = PluginHarness::create(Greeter::default()).unwrap();
+        harness
+            .send(&PlayerJoinEvent::create(PlayerInfo::create(1, "alice")))
+            .unwrap();
+        assert!(harness
+            .send(&PlayerJoinEvent::create(PlayerInfo::create(2, "")))
+            .is_err());
+
+        let event = CommandCompleteEvent::create(CommandSender::Console, "greet ", "greet", "player");
+        assert_eq!(harness.send(&event).unwrap(), vec!["alice".to_string()]);
+        assert_eq!(harness.get_history().len(), 3);
+        assert!(harness.unload().is_ok());
+    }
+}
@@ -0,0 +1,48 @@
+use extism_pdk::LogLevel;
+
+use super::service::PluginService;
+
+/// Writes into the server log on behalf of the plugin
+#[derive(Default)]
+pub struct PluginLogger;
+
+impl PluginService for PluginLogger {
+    fn inject() -> Self {
+        Self
+    }
+}
+
+impl PluginLogger {
+    pub fn debug(&self, message: impl AsRef<str>) {
+        write(LogLevel::Debug, message.as_ref());
+    }
+
+    pub fn info(&self, message: impl AsRef<str>) {
+        write(LogLevel::Info, message.as_ref());
+    }
+
+    pub fn warn(&self, message: impl AsRef<str>) {
+        write(LogLevel::Warn, message.as_ref());
+    }
+
+    pub fn error(&self, message: impl AsRef<str>) {
+        write(LogLevel::Error, message.as_ref());
+    }
+}
+
+#[cfg(not(feature = "mock-host"))]
+fn write(level: LogLevel, message: &str) {
+    extism_pdk::log!(level, "{}", message);
+}
+
+#[cfg(feature = "mock-host")]
+fn write(level: LogLevel, message: &str) {
+    let level = match level {
+        LogLevel::Debug => "debug",
+        LogLevel::Info => "info",
+        LogLevel::Warn => "warn",
+        LogLevel::Error => "error",
+        LogLevel::Trace => "trace",
+    };
+    super::mock_host::MockHost::with(|host| host.log(level, message));
+}
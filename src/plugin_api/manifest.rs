@@ -0,0 +1,494 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+    str::FromStr,
+};
+
+/// Version of the plugin API implemented by this crate;
+/// plugins built for another version are refused.
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+const REGEX_PLUGIN_SLUG: &str = r"^[a-z0-9_-]{2,32}$";
+
+/// Semantic version "major.minor.patch"
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PluginVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl PluginVersion {
+    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self { major, minor, patch }
+    }
+}
+
+impl FromStr for PluginVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().split('.').collect();
+        if parts.is_empty() || parts.len() > 3 {
+            return Err(format!("&cversion &4\"{}\" &cmust be \"major.minor.patch\"", s));
+        }
+        let mut numbers = [0_u32; 3];
+        for (i, part) in parts.iter().enumerate() {
+            numbers[i] = part
+                .parse()
+                .map_err(|_| format!("&cversion &4\"{}\" &cmust be \"major.minor.patch\"", s))?;
+        }
+        Ok(Self::new(numbers[0], numbers[1], numbers[2]))
+    }
+}
+
+impl Display for PluginVersion {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl Serialize for PluginVersion {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for PluginVersion {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(VersionVisitor(PhantomData))
+    }
+}
+
+/// Accepts unquoted yaml versions like `1`, which are parsed as numbers.
+///
+/// Unquoted `1.2` is rejected: floats lose trailing zeros, so `1.10` would be read as "1.1".
+struct VersionVisitor<T>(PhantomData<T>);
+
+impl<T: FromStr<Err = String>> serde::de::Visitor<'_> for VersionVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(f, "a version string")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<T, E> {
+        v.parse().map_err(E::custom)
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<T, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<T, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<T, E> {
+        Err(E::custom(format!(
+            "&cversion &4{} &cmust be quoted, like &4\"1.2.0\"",
+            v
+        )))
+    }
+}
+
+/// Dependency version requirement: "=1.2.0", ">=1.2.0" or "^1.2".
+///
+/// "^" follows semver: the leftmost non-zero part must match, so "^1.2" allows 1.x
+/// starting from 1.2, "^0.3" allows only 0.3.x and "^0.0.3" only 0.0.3.
+/// Version without an operator is treated as "^".
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VersionRequirement {
+    Exact(PluginVersion),
+    AtLeast(PluginVersion),
+    Compatible(PluginVersion),
+}
+
+impl VersionRequirement {
+    pub fn matches(&self, version: &PluginVersion) -> bool {
+        match self {
+            VersionRequirement::Exact(v) => version == v,
+            VersionRequirement::AtLeast(v) => version >= v,
+            VersionRequirement::Compatible(v) => {
+                let compatible = match (v.major, v.minor) {
+                    (0, 0) => version.major == 0 && version.minor == 0 && version.patch == v.patch,
+                    (0, _) => version.major == 0 && version.minor == v.minor,
+                    _ => version.major == v.major,
+                };
+                compatible && version >= v
+            }
+        }
+    }
+}
+
+impl FromStr for VersionRequirement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(v) = s.strip_prefix(">=") {
+            return Ok(VersionRequirement::AtLeast(v.parse()?));
+        }
+        if let Some(v) = s.strip_prefix('=') {
+            return Ok(VersionRequirement::Exact(v.parse()?));
+        }
+        Ok(VersionRequirement::Compatible(
+            s.strip_prefix('^').unwrap_or(s).parse()?,
+        ))
+    }
+}
+
+impl Display for VersionRequirement {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            VersionRequirement::Exact(v) => write!(f, "={}", v),
+            VersionRequirement::AtLeast(v) => write!(f, ">={}", v),
+            VersionRequirement::Compatible(v) => write!(f, "^{}", v),
+        }
+    }
+}
+
+impl Serialize for VersionRequirement {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for VersionRequirement {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(VersionVisitor(PhantomData))
+    }
+}
+
+/// Access to host functions which must be declared by the plugin
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginCapability {
+    WorldRead,
+    WorldWrite,
+    Storage,
+    Commands,
+    WorldGenerator,
+    Messaging,
+    Blocks,
+}
+
+impl PluginCapability {
+    /// Capability required to call the host function; `None` if any plugin can call it.
+    pub fn for_host_function(name: &str) -> Option<PluginCapability> {
+        match name {
+            "has_world_raw" | "get_block_raw" | "get_chunk_raw" => Some(PluginCapability::WorldRead),
+            "create_world_raw" | "set_block_raw" | "fill_region_raw" => Some(PluginCapability::WorldWrite),
+            "storage_get_raw" | "storage_set_raw" | "storage_remove_raw" => Some(PluginCapability::Storage),
+            "register_command_raw" => Some(PluginCapability::Commands),
+            "register_world_generator_raw" => Some(PluginCapability::WorldGenerator),
+            "subscribe_raw" | "unsubscribe_raw" | "publish_raw" | "request_raw" => Some(PluginCapability::Messaging),
+            "register_block_raw" | "register_resource_raw" => Some(PluginCapability::Blocks),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PluginDependency {
+    slug: String,
+    version: VersionRequirement,
+    #[serde(default)]
+    optional: bool,
+}
+
+impl PluginDependency {
+    pub fn get_slug(&self) -> &String {
+        &self.slug
+    }
+
+    pub fn get_version(&self) -> &VersionRequirement {
+        &self.version
+    }
+
+    pub fn is_optional(&self) -> bool {
+        self.optional
+    }
+}
+
+/// Plugin metadata, "plugin.yml" in the plugin archive.
+///
+/// ```yaml
+/// slug: warps
+/// version: 1.2.0
+/// api_version: 1
+/// dependencies:
+///   - slug: economy
+///     version: ">=0.3"
+/// events: [on_plugin_load, on_command_executed]
+/// capabilities: [storage, commands]
+/// state_version: 1
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PluginManifest {
+    slug: String,
+    version: PluginVersion,
+    api_version: u32,
+
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+
+    #[serde(default)]
+    dependencies: Vec<PluginDependency>,
+
+    /// Exported event handlers; the host sends only these events to the plugin
+    #[serde(default)]
+    events: Vec<String>,
+
+    #[serde(default)]
+    capabilities: Vec<PluginCapability>,
+
+    /// Version of the state accepted from the previous instance on reload
+    #[serde(default)]
+    state_version: u32,
+
+    /// Plugins with lower priority receive chained events like `on_chunk_populate` first
+    #[serde(default)]
+    priority: i32,
+}
+
+impl PluginManifest {
+    pub fn from_yaml(yaml: &str) -> Result<Self, String> {
+        let manifest: Self = serde_yaml::from_str(yaml).map_err(|e| format!("&cplugin manifest error: &4{}", e))?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let re = regex::Regex::new(REGEX_PLUGIN_SLUG).unwrap();
+        if !re.is_match(&self.slug) {
+            return Err(format!(
+                "&cplugin slug &4\"{}\" &cmust contain only lowercase letters, numbers, \"-\" and \"_\"",
+                self.slug
+            ));
+        }
+        if self.api_version != PLUGIN_API_VERSION {
+            return Err(format!(
+                "&cplugin &4\"{}\" &crequires api version &4{}&c, server api version is &4{}",
+                self.slug, self.api_version, PLUGIN_API_VERSION
+            ));
+        }
+
+        for (i, dependency) in self.dependencies.iter().enumerate() {
+            if !re.is_match(&dependency.slug) {
+                return Err(format!("&cdependency slug &4\"{}\" &cis invalid", dependency.slug));
+            }
+            if dependency.slug == self.slug {
+                return Err(format!("&cplugin &4\"{}\" &ccan't depend on itself", self.slug));
+            }
+            if self.dependencies[..i].iter().any(|d| d.slug == dependency.slug) {
+                return Err(format!("&cdependency &4\"{}\" &cis declared twice", dependency.slug));
+            }
+        }
+
+        for (i, event) in self.events.iter().enumerate() {
+            if !event.starts_with("on_") {
+                return Err(format!("&cevent &4\"{}\" &cmust start with \"on_\"", event));
+            }
+            if self.events[..i].contains(event) {
+                return Err(format!("&cevent &4\"{}\" &cis declared twice", event));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that all required dependencies are loaded with matching versions
+    pub fn check_dependencies(&self, loaded: &HashMap<String, PluginVersion>) -> Result<(), String> {
+        for dependency in self.dependencies.iter() {
+            match loaded.get(&dependency.slug) {
+                Some(version) => {
+                    if !dependency.version.matches(version) {
+                        return Err(format!(
+                            "&cplugin &4\"{}\" &crequires &4\"{}\" {}&c, but &4{} &cis loaded",
+                            self.slug, dependency.slug, dependency.version, version
+                        ));
+                    }
+                }
+                None => {
+                    if !dependency.optional {
+                        return Err(format!(
+                            "&cplugin &4\"{}\" &crequires &4\"{}\"",
+                            self.slug, dependency.slug
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Must be called by the host before every host function call of the plugin
+    pub fn check_host_function(&self, name: &str) -> Result<(), String> {
+        match PluginCapability::for_host_function(name) {
+            Some(capability) if !self.has_capability(capability) => Err(format!(
+                "&cplugin &4\"{}\" &ccalled &4\"{}\" &cwithout declared capability &4{:?}",
+                self.slug, name, capability
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn has_capability(&self, capability: PluginCapability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+
+    pub fn declares_event(&self, export_name: &str) -> bool {
+        self.events.iter().any(|e| e == export_name)
+    }
+
+    pub fn get_slug(&self) -> &String {
+        &self.slug
+    }
+
+    pub fn get_version(&self) -> &PluginVersion {
+        &self.version
+    }
+
+    pub fn get_api_version(&self) -> u32 {
+        self.api_version
+    }
+
+    pub fn get_title(&self) -> Option<&String> {
+        self.title.as_ref()
+    }
+
+    pub fn get_description(&self) -> Option<&String> {
+        self.description.as_ref()
+    }
+
+    pub fn get_dependencies(&self) -> &Vec<PluginDependency> {
+        &self.dependencies
+    }
+
+    pub fn get_events(&self) -> &Vec<String> {
+        &self.events
+    }
+
+    pub fn get_capabilities(&self) -> &Vec<PluginCapability> {
+        &self.capabilities
+    }
+
+    pub fn get_state_version(&self) -> u32 {
+        self.state_version
+    }
+
+    pub fn get_priority(&self) -> i32 {
+        self.priority
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{PluginCapability, PluginManifest, PluginVersion, VersionRequirement};
+
+    const MANIFEST: &str = r#"
+slug: warps
+version: 1.2.0
+api_version: 1
+dependencies:
+  - slug: economy
+    version: ">=0.3"
+  - slug: maps
+    version: "1.1"
+    optional: true
+events: [on_plugin_load, on_command_executed]
+capabilities: [storage, commands]
+"#;
+
+    #[test]
+    fn test_manifest_parse() {
+        let manifest = PluginManifest::from_yaml(MANIFEST).unwrap();
+        assert_eq!(manifest.get_slug(), "warps");
+        assert_eq!(manifest.get_version(), &PluginVersion::new(1, 2, 0));
+        assert!(manifest.declares_event("on_plugin_load"));
+        assert!(!manifest.declares_event("on_chat_message"));
+        assert!(manifest.has_capability(PluginCapability::Storage));
+
+        assert!(manifest.check_host_function("storage_get_raw").is_ok());
+        assert!(manifest.check_host_function("get_plugin_slug_raw").is_ok());
+        assert!(manifest.check_host_function("set_block_raw").is_err());
+    }
+
+    #[test]
+    fn test_manifest_invalid() {
+        let yaml = MANIFEST.replace("slug: warps", "slug: Warps!");
+        assert!(PluginManifest::from_yaml(&yaml).is_err());
+
+        let yaml = MANIFEST.replace("api_version: 1", "api_version: 999");
+        assert!(PluginManifest::from_yaml(&yaml).is_err());
+
+        let yaml = MANIFEST.replace("[storage, commands]", "[storage, teleport]");
+        assert!(PluginManifest::from_yaml(&yaml).is_err());
+
+        let yaml = MANIFEST.replace("on_command_executed", "on_plugin_load");
+        assert!(PluginManifest::from_yaml(&yaml).is_err());
+    }
+
+    #[test]
+    fn test_manifest_dependencies() {
+        let manifest = PluginManifest::from_yaml(MANIFEST).unwrap();
+
+        let mut loaded = HashMap::new();
+        assert!(manifest.check_dependencies(&loaded).is_err());
+
+        loaded.insert("economy".to_string(), PluginVersion::new(0, 2, 9));
+        assert!(manifest.check_dependencies(&loaded).is_err());
+
+        loaded.insert("economy".to_string(), PluginVersion::new(0, 3, 0));
+        assert!(manifest.check_dependencies(&loaded).is_ok());
+
+        loaded.insert("maps".to_string(), PluginVersion::new(2, 0, 0));
+        assert!(manifest.check_dependencies(&loaded).is_err());
+    }
+
+    #[test]
+    fn test_version_requirement() {
+        let requirement: VersionRequirement = "1.2".parse().unwrap();
+        assert!(requirement.matches(&PluginVersion::new(1, 4, 0)));
+        assert!(!requirement.matches(&PluginVersion::new(1, 1, 9)));
+        assert!(!requirement.matches(&PluginVersion::new(2, 0, 0)));
+
+        let requirement: VersionRequirement = "=1.2.3".parse().unwrap();
+        assert!(requirement.matches(&PluginVersion::new(1, 2, 3)));
+        assert!(!requirement.matches(&PluginVersion::new(1, 2, 4)));
+
+        let requirement: VersionRequirement = "^0.3".parse().unwrap();
+        assert!(requirement.matches(&PluginVersion::new(0, 3, 7)));
+        assert!(!requirement.matches(&PluginVersion::new(0, 9, 0)));
+
+        let requirement: VersionRequirement = "^0.0.3".parse().unwrap();
+        assert!(requirement.matches(&PluginVersion::new(0, 0, 3)));
+        assert!(!requirement.matches(&PluginVersion::new(0, 0, 4)));
+
+        assert!("1.x".parse::<VersionRequirement>().is_err());
+    }
+
+    #[test]
+    fn test_version_unquoted() {
+        let yaml = MANIFEST.replace("version: 1.2.0", "version: 3");
+        let manifest = PluginManifest::from_yaml(&yaml).unwrap();
+        assert_eq!(manifest.get_version(), &PluginVersion::new(3, 0, 0));
+
+        // Floats are ambiguous: 1.10 is read as 1.1
+        for version in ["1.2", "1.10"] {
+            let yaml = MANIFEST.replace("version: 1.2.0", &format!("version: {}", version));
+            assert!(PluginManifest::from_yaml(&yaml).is_err());
+        }
+        let yaml = MANIFEST.replace("version: \"1.1\"", "version: 1.1");
+        assert!(PluginManifest::from_yaml(&yaml).is_err());
+        let yaml = MANIFEST.replace("version: 1.2.0", "version: \"1.10\"");
+        let manifest = PluginManifest::from_yaml(&yaml).unwrap();
+        assert_eq!(manifest.get_version(), &PluginVersion::new(1, 10, 0));
+    }
+}
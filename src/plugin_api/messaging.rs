@@ -0,0 +1,335 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+use super::{
+    events::plugin_message::{PluginMessageEvent, PluginRequestEvent},
+    handler_result::parse_handler_output,
+};
+
+/// Request timeout used when the plugin passes zero timeout
+pub const DEFAULT_REQUEST_TIMEOUT_MS: u64 = 1000;
+
+/// Wire type of the message publishing
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PublishRequest {
+    channel: String,
+    payload: serde_json::Value,
+}
+
+impl PublishRequest {
+    pub fn create(channel: impl Into<String>, payload: serde_json::Value) -> Self {
+        Self {
+            channel: channel.into(),
+            payload,
+        }
+    }
+
+    pub fn get_channel(&self) -> &String {
+        &self.channel
+    }
+
+    pub fn get_payload(&self) -> &serde_json::Value {
+        &self.payload
+    }
+}
+
+/// Wire type of the call to another plugin
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PluginRequest {
+    target: String,
+    channel: String,
+    payload: serde_json::Value,
+    timeout_ms: u64,
+}
+
+impl PluginRequest {
+    pub fn create(
+        target: impl Into<String>,
+        channel: impl Into<String>,
+        payload: serde_json::Value,
+        timeout_ms: u64,
+    ) -> Self {
+        Self {
+            target: target.into(),
+            channel: channel.into(),
+            payload,
+            timeout_ms,
+        }
+    }
+
+    /// Slug of the plugin which must answer
+    pub fn get_target(&self) -> &String {
+        &self.target
+    }
+
+    pub fn get_channel(&self) -> &String {
+        &self.channel
+    }
+
+    pub fn get_payload(&self) -> &serde_json::Value {
+        &self.payload
+    }
+
+    pub fn get_timeout_ms(&self) -> u64 {
+        match self.timeout_ms {
+            0 => DEFAULT_REQUEST_TIMEOUT_MS,
+            timeout => timeout,
+        }
+    }
+
+    pub fn to_event(&self, sender: &str) -> PluginRequestEvent {
+        PluginRequestEvent::create(sender, self.channel.clone(), self.payload.clone())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum PluginResponse {
+    Ok { value: serde_json::Value },
+    Error { message: String },
+    NotFound,
+    Timeout,
+}
+
+impl PluginResponse {
+    /// Builds the response from the `on_plugin_request` handler output of the target plugin
+    pub fn from_output(output: &str) -> Self {
+        match parse_handler_output::<serde_json::Value>(output) {
+            Ok(value) => PluginResponse::Ok { value },
+            Err(e) => PluginResponse::Error { message: e.to_string() },
+        }
+    }
+}
+
+/// Host side routing of the plugin messages.
+///
+/// Keeps the channel subscriptions and the chain of the running requests,
+/// because a plugin can't be called while it waits for its own request.
+#[derive(Default)]
+pub struct MessageRouter {
+    subscriptions: HashMap<String, BTreeSet<String>>,
+    running_requests: Vec<String>,
+}
+
+impl MessageRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn subscribe(&mut self, plugin_slug: &str, channel: &str) {
+        self.subscriptions
+            .entry(channel.to_string())
+            .or_default()
+            .insert(plugin_slug.to_string());
+    }
+
+    pub fn unsubscribe(&mut self, plugin_slug: &str, channel: &str) {
+        if let Some(subscribers) = self.subscriptions.get_mut(channel) {
+            subscribers.remove(plugin_slug);
+            if subscribers.is_empty() {
+                self.subscriptions.remove(channel);
+            }
+        }
+    }
+
+    /// Removes all subscriptions of the unloaded plugin
+    pub fn remove_plugin(&mut self, plugin_slug: &str) {
+        for subscribers in self.subscriptions.values_mut() {
+            subscribers.remove(plugin_slug);
+        }
+        self.subscriptions.retain(|_, subscribers| !subscribers.is_empty());
+    }
+
+    pub fn get_subscribers(&self, channel: &str) -> impl Iterator<Item = &String> {
+        self.subscriptions.get(channel).into_iter().flatten()
+    }
+
+    /// Returns events for every subscriber except the sender
+    pub fn publish(&self, sender: &str, request: &PublishRequest) -> Vec<(String, PluginMessageEvent)> {
+        self.get_subscribers(&request.channel)
+            .filter(|slug| *slug != sender)
+            .map(|slug| {
+                let event = PluginMessageEvent::create(sender, request.channel.clone(), request.payload.clone());
+                (slug.clone(), event)
+            })
+            .collect()
+    }
+
+    /// Must be called before the target plugin is called;
+    /// fails if the target is already waiting for a response somewhere in the chain.
+    pub fn begin_request(&mut self, sender: &str, request: &PluginRequest) -> Result<(), PluginResponse> {
+        // The sender is the root of a new chain
+        let waiting = if self.running_requests.is_empty() {
+            request.target == sender
+        } else {
+            self.running_requests.contains(&request.target)
+        };
+        if waiting {
+            return Err(PluginResponse::Error {
+                message: format!("&cplugin &4\"{}\" &cis waiting for a response", request.target),
+            });
+        }
+        if self.running_requests.is_empty() {
+            self.running_requests.push(sender.to_string());
+        }
+        self.running_requests.push(request.target.clone());
+        Ok(())
+    }
+
+    pub fn end_request(&mut self) {
+        self.running_requests.pop();
+        if self.running_requests.len() == 1 {
+            self.running_requests.clear();
+        }
+    }
+}
+
+#[cfg(feature = "wasm-plugin")]
+pub use self::plugin::PluginMessenger;
+
+#[cfg(feature = "wasm-plugin")]
+mod plugin {
+    use serde::{de::DeserializeOwned, Serialize};
+    use std::time::Duration;
+
+    use super::{PluginRequest, PluginResponse, PublishRequest};
+    use crate::plugin_api::service::PluginService;
+
+    #[cfg(not(feature = "mock-host"))]
+    #[extism_pdk::host_fn]
+    extern "ExtismHost" {
+        fn subscribe_raw(channel: String) -> ();
+        fn unsubscribe_raw(channel: String) -> ();
+        fn publish_raw(request: String) -> ();
+        fn request_raw(request: String) -> String;
+    }
+
+    #[cfg(feature = "mock-host")]
+    use crate::plugin_api::mock_host::host::{publish_raw, request_raw, subscribe_raw, unsubscribe_raw};
+
+    /// Messages between plugins.
+    ///
+    /// Subscribed channels are delivered as
+    /// [`PluginMessageEvent`](crate::plugin_api::events::plugin_message::PluginMessageEvent),
+    /// requests as [`PluginRequestEvent`](crate::plugin_api::events::plugin_message::PluginRequestEvent).
+    #[derive(Default)]
+    pub struct PluginMessenger;
+
+    impl PluginService for PluginMessenger {
+        fn inject() -> Self {
+            Self
+        }
+    }
+
+    impl PluginMessenger {
+        pub fn subscribe(&self, channel: &str) -> Result<(), extism_pdk::Error> {
+            unsafe { subscribe_raw(channel.to_string()) }
+        }
+
+        pub fn unsubscribe(&self, channel: &str) -> Result<(), extism_pdk::Error> {
+            unsafe { unsubscribe_raw(channel.to_string()) }
+        }
+
+        pub fn publish<T: Serialize>(&self, channel: &str, message: &T) -> Result<(), extism_pdk::Error> {
+            let request = PublishRequest::create(channel, serde_json::to_value(message)?);
+            unsafe { publish_raw(serde_json::to_string(&request)?) }
+        }
+
+        /// Calls `on_plugin_request` of the target plugin and waits for the response
+        pub fn request<T: Serialize, R: DeserializeOwned>(
+            &self,
+            target: &str,
+            channel: &str,
+            message: &T,
+            timeout: Duration,
+        ) -> Result<R, extism_pdk::Error> {
+            let request = PluginRequest::create(
+                target,
+                channel,
+                serde_json::to_value(message)?,
+                timeout.as_millis() as u64,
+            );
+            let result = unsafe { request_raw(serde_json::to_string(&request)?)? };
+            match serde_json::from_str(&result)? {
+                PluginResponse::Ok { value } => Ok(serde_json::from_value(value)?),
+                PluginResponse::Error { message } => Err(extism_pdk::Error::msg(message)),
+                PluginResponse::NotFound => Err(extism_pdk::Error::msg(format!("Plugin \"{}\" not found", target))),
+                PluginResponse::Timeout => Err(extism_pdk::Error::msg(format!(
+                    "Plugin \"{}\" request timed out",
+                    target
+                ))),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MessageRouter, PluginRequest, PluginResponse, PublishRequest};
+    use crate::plugin_api::handler_result::{HandlerErrorKind, HandlerResult};
+
+    #[test]
+    fn test_router_publish() {
+        let mut router = MessageRouter::new();
+        router.subscribe("economy", "balance");
+        router.subscribe("quests", "balance");
+        router.subscribe("quests", "kills");
+
+        let events = router.publish("economy", &PublishRequest::create("balance", serde_json::json!(10)));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, "quests");
+        assert_eq!(events[0].1.get_sender(), "economy");
+        assert_eq!(events[0].1.get_payload_as::<u32>().unwrap(), 10);
+
+        router.remove_plugin("quests");
+        assert_eq!(router.get_subscribers("kills").count(), 0);
+        router.unsubscribe("economy", "balance");
+        assert_eq!(router.get_subscribers("balance").count(), 0);
+    }
+
+    #[test]
+    fn test_router_request_chain() {
+        let mut router = MessageRouter::new();
+        let request = PluginRequest::create("economy", "balance", serde_json::Value::Null, 100);
+        assert!(router.begin_request("quests", &request).is_ok());
+
+        // economy calls back quests while quests waits for economy
+        let callback = PluginRequest::create("quests", "progress", serde_json::Value::Null, 100);
+        assert!(router.begin_request("economy", &callback).is_err());
+
+        let nested = PluginRequest::create("permissions", "check", serde_json::Value::Null, 100);
+        assert!(router.begin_request("economy", &nested).is_ok());
+        router.end_request();
+        router.end_request();
+        assert!(router.begin_request("economy", &callback).is_ok());
+    }
+
+    #[test]
+    fn test_router_self_request() {
+        let mut router = MessageRouter::new();
+        let request = PluginRequest::create("quests", "progress", serde_json::Value::Null, 100);
+        assert!(router.begin_request("quests", &request).is_err());
+
+        // The failed request must not leave the sender in the chain
+        assert!(router.begin_request("economy", &request).is_ok());
+        router.end_request();
+        assert!(router.begin_request("economy", &request).is_ok());
+    }
+
+    #[test]
+    fn test_response_from_output() {
+        let output = serde_json::to_string(&HandlerResult::ok(serde_json::json!({"balance": 5}))).unwrap();
+        assert_eq!(
+            PluginResponse::from_output(&output),
+            PluginResponse::Ok {
+                value: serde_json::json!({"balance": 5})
+            }
+        );
+
+        let output = serde_json::to_string(&HandlerResult::err(HandlerErrorKind::Handler, "no account")).unwrap();
+        assert!(matches!(
+            PluginResponse::from_output(&output),
+            PluginResponse::Error { .. }
+        ));
+    }
+}
@@ -0,0 +1,557 @@
+//! In-memory fake of the server host functions.
+//!
+//! With the `mock-host` feature every `extern "ExtismHost"` function of the plugin api
+//! is replaced by the function from this module, so the plugin code can be tested natively:
+//!
+//! ```ignore
+//! MockHost::reset();
+//! MockHost::with(|host| host.create_world("default"));
+//! WorldsManager.set_block("default", &BlockPosition::new(0, 10, 0), Some(BlockDataInfo::create(1)))?;
+//! assert_eq!(MockHost::with(|host| host.get_calls_of("set_block_raw").len()), 1);
+//! ```
+//!
+//! The state is kept per thread, so every test has its own host.
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap},
+};
+
+use crate::{
+    chunks::{
+        block_position::{BlockPosition, BlockPositionTrait},
+        chunk_data::{BlockDataInfo, ChunkData, ChunkSectionData},
+        chunk_position::ChunkPosition,
+    },
+    commands::command::Command,
+};
+
+use super::{
+    custom_blocks::PluginBlockRegistry,
+    events::scheduled_task::ScheduledTaskEvent,
+    manifest::PluginManifest,
+    messaging::{PluginResponse, PublishRequest},
+    scheduler::PluginTaskScheduler,
+};
+
+thread_local! {
+    static MOCK_HOST: RefCell<MockHost> = RefCell::new(MockHost::default());
+}
+
+/// Host function call made by the plugin
+#[derive(Clone, Debug, PartialEq)]
+pub struct MockHostCall {
+    name: String,
+    input: String,
+}
+
+impl MockHostCall {
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_input(&self) -> &String {
+        &self.input
+    }
+}
+
+pub struct MockHost {
+    plugin_slug: String,
+    manifest: Option<PluginManifest>,
+    config: String,
+    worlds: HashMap<String, HashMap<ChunkPosition, ChunkData>>,
+    storage: HashMap<String, serde_json::Value>,
+    world_generators: Vec<String>,
+    blocks: PluginBlockRegistry,
+    commands: Vec<Command>,
+    scheduler: PluginTaskScheduler,
+    subscriptions: BTreeSet<String>,
+    published: Vec<PublishRequest>,
+    responses: HashMap<(String, String), PluginResponse>,
+    logs: Vec<(String, String)>,
+    calls: Vec<MockHostCall>,
+}
+
+impl Default for MockHost {
+    fn default() -> Self {
+        Self {
+            plugin_slug: "mock".to_string(),
+            manifest: None,
+            config: Default::default(),
+            worlds: Default::default(),
+            storage: Default::default(),
+            world_generators: Default::default(),
+            blocks: Default::default(),
+            commands: Default::default(),
+            scheduler: Default::default(),
+            subscriptions: Default::default(),
+            published: Default::default(),
+            responses: Default::default(),
+            logs: Default::default(),
+            calls: Default::default(),
+        }
+    }
+}
+
+impl MockHost {
+    /// Runs the closure with the host of the current thread
+    pub fn with<R>(f: impl FnOnce(&mut MockHost) -> R) -> R {
+        MOCK_HOST.with(|host| f(&mut host.borrow_mut()))
+    }
+
+    /// Clears all the state of the current thread
+    pub fn reset() {
+        Self::with(|host| *host = MockHost::default());
+    }
+
+    pub fn set_plugin_slug(&mut self, slug: impl Into<String>) {
+        self.plugin_slug = slug.into();
+    }
+
+    /// Host functions are checked against the manifest capabilities like on the server
+    pub fn set_manifest(&mut self, manifest: PluginManifest) {
+        self.plugin_slug = manifest.get_slug().clone();
+        self.manifest = Some(manifest);
+    }
+
+    /// Yaml returned by [`PluginConfig`](super::config::PluginConfig)
+    pub fn set_config(&mut self, yaml: impl Into<String>) {
+        self.config = yaml.into();
+    }
+
+    /// Response of the `request` to the target plugin; unknown requests get `NotFound`
+    pub fn set_response(&mut self, target: &str, channel: &str, response: PluginResponse) {
+        self.responses
+            .insert((target.to_string(), channel.to_string()), response);
+    }
+
+    pub fn create_world(&mut self, slug: &str) {
+        self.worlds.entry(slug.to_string()).or_default();
+    }
+
+    pub fn has_world(&self, slug: &str) -> bool {
+        self.worlds.contains_key(slug)
+    }
+
+    /// Inserts the chunk into the world, creating the world if needed
+    pub fn load_chunk(&mut self, slug: &str, chunk_position: ChunkPosition, chunk_data: ChunkData) {
+        self.worlds
+            .entry(slug.to_string())
+            .or_default()
+            .insert(chunk_position, chunk_data);
+    }
+
+    pub fn get_chunk(&self, slug: &str, chunk_position: &ChunkPosition) -> Option<&ChunkData> {
+        self.worlds.get(slug)?.get(chunk_position)
+    }
+
+    /// `None` for air and for blocks of unloaded chunks
+    pub fn get_block(&self, slug: &str, position: &BlockPosition) -> Option<BlockDataInfo> {
+        let chunk = self.get_chunk(slug, &position.get_chunk_position())?;
+        let (section, _) = position.get_block_position();
+        if section as usize >= chunk.len() {
+            return None;
+        }
+        chunk.get_block_info(position)
+    }
+
+    /// Missing chunks are created empty; returns true if the block was changed
+    pub fn set_block(
+        &mut self,
+        slug: &str,
+        position: &BlockPosition,
+        block: Option<BlockDataInfo>,
+    ) -> Result<bool, String> {
+        let max_y = crate::VERTICAL_SECTIONS as i64 * crate::CHUNK_SIZE as i64;
+        if position.get_y() < 0 || position.get_y() >= max_y {
+            return Err(format!("&cblock &4{:?} &cis outside of the world height", position));
+        }
+        if self.get_block(slug, position) == block {
+            return Ok(false);
+        }
+        let Some(world) = self.worlds.get_mut(slug) else {
+            return Err(format!("&cworld &4\"{}\" &cnot found", slug));
+        };
+        let chunk = world.entry(position.get_chunk_position()).or_default();
+        while chunk.len() < crate::VERTICAL_SECTIONS {
+            chunk.push_section(ChunkSectionData::default());
+        }
+        let (section, chunk_block_position) = position.get_block_position();
+        chunk.change_block(section, &chunk_block_position, block);
+        Ok(true)
+    }
+
+    pub fn get_storage_value(&self, key: &str) -> Option<&serde_json::Value> {
+        self.storage.get(key)
+    }
+
+    pub fn get_world_generators(&self) -> &Vec<String> {
+        &self.world_generators
+    }
+
+    /// Blocks and resources registered by the plugin
+    pub fn get_blocks(&self) -> &PluginBlockRegistry {
+        &self.blocks
+    }
+
+    pub fn get_commands(&self) -> &Vec<Command> {
+        &self.commands
+    }
+
+    pub fn get_scheduler(&self) -> &PluginTaskScheduler {
+        &self.scheduler
+    }
+
+    /// Advances the scheduler and returns the due tasks of the plugin
+    pub fn tick(&mut self) -> Vec<ScheduledTaskEvent> {
+        self.scheduler.tick().into_iter().map(|(_, event)| event).collect()
+    }
+
+    pub fn get_subscriptions(&self) -> &BTreeSet<String> {
+        &self.subscriptions
+    }
+
+    pub fn get_published(&self) -> &Vec<PublishRequest> {
+        &self.published
+    }
+
+    /// Plugin log as (level, message)
+    pub fn get_logs(&self) -> &Vec<(String, String)> {
+        &self.logs
+    }
+
+    pub fn get_calls(&self) -> &Vec<MockHostCall> {
+        &self.calls
+    }
+
+    pub fn get_calls_of(&self, name: &str) -> Vec<&MockHostCall> {
+        self.calls.iter().filter(|c| c.name == name).collect()
+    }
+
+    pub(crate) fn log(&mut self, level: &str, message: &str) {
+        self.logs.push((level.to_string(), message.to_string()));
+    }
+
+    fn record(&mut self, name: &str, input: impl Into<String>) -> Result<(), extism_pdk::Error> {
+        self.calls.push(MockHostCall {
+            name: name.to_string(),
+            input: input.into(),
+        });
+        if let Some(manifest) = self.manifest.as_ref() {
+            manifest.check_host_function(name).map_err(extism_pdk::Error::msg)?;
+        }
+        Ok(())
+    }
+}
+
+/// Replacements of the `extern "ExtismHost"` functions with the same signatures
+pub(crate) mod host {
+    use super::MockHost;
+    use crate::{
+        blocks::block_type::BlockTypeManifest,
+        commands::command::Command,
+        plugin_api::{
+            custom_blocks::RegisterResourceRequest,
+            messaging::{PluginRequest, PluginResponse, PublishRequest},
+            scheduler::ScheduleTaskRequest,
+            storage::StorageSetRequest,
+            world_access::{ChunkReadResponse, FillRegionRequest, GetBlockRequest, GetChunkRequest, SetBlockRequest},
+        },
+        utils::compressable::Compressable,
+    };
+
+    type HostResult<T> = Result<T, extism_pdk::Error>;
+
+    pub(crate) unsafe fn has_world_raw(slug: String) -> HostResult<String> {
+        MockHost::with(|host| {
+            host.record("has_world_raw", slug.clone())?;
+            Ok(host.has_world(&slug).to_string())
+        })
+    }
+
+    pub(crate) unsafe fn create_world_raw(slug: String) -> HostResult<()> {
+        MockHost::with(|host| {
+            host.record("create_world_raw", slug.clone())?;
+            if host.has_world(&slug) {
+                return Err(extism_pdk::Error::msg(format!("World \"{}\" already exists", slug)));
+            }
+            host.create_world(&slug);
+            Ok(())
+        })
+    }
+
+    pub(crate) unsafe fn get_block_raw(request: String) -> HostResult<String> {
+        MockHost::with(|host| {
+            host.record("get_block_raw", request.clone())?;
+            let request: GetBlockRequest = serde_json::from_str(&request)?;
+            let block = host.get_block(request.get_world_slug(), request.get_position());
+            Ok(serde_json::to_string(&block)?)
+        })
+    }
+
+    pub(crate) unsafe fn set_block_raw(request: String) -> HostResult<()> {
+        MockHost::with(|host| {
+            host.record("set_block_raw", request.clone())?;
+            let request: SetBlockRequest = serde_json::from_str(&request)?;
+            host.set_block(request.get_world_slug(), request.get_position(), *request.get_block())
+                .map_err(extism_pdk::Error::msg)?;
+            Ok(())
+        })
+    }
+
+    pub(crate) unsafe fn fill_region_raw(request: String) -> HostResult<String> {
+        MockHost::with(|host| {
+            host.record("fill_region_raw", request.clone())?;
+            let request: FillRegionRequest = serde_json::from_str(&request)?;
+            request.validate().map_err(extism_pdk::Error::msg)?;
+            let mut changed = 0_u64;
+            for position in request.iter_positions() {
+                if host
+                    .set_block(request.get_world_slug(), &position, *request.get_block())
+                    .map_err(extism_pdk::Error::msg)?
+                {
+                    changed += 1;
+                }
+            }
+            Ok(serde_json::to_string(&changed)?)
+        })
+    }
+
+    pub(crate) unsafe fn get_chunk_raw(request: String) -> HostResult<Vec<u8>> {
+        MockHost::with(|host| {
+            host.record("get_chunk_raw", request.clone())?;
+            let request: GetChunkRequest = serde_json::from_str(&request)?;
+            let chunk = host
+                .get_chunk(request.get_world_slug(), request.get_chunk_position())
+                .cloned();
+            Ok(ChunkReadResponse::create(chunk).encode())
+        })
+    }
+
+    pub(crate) unsafe fn storage_get_raw(key: String) -> HostResult<String> {
+        MockHost::with(|host| {
+            host.record("storage_get_raw", key.clone())?;
+            Ok(serde_json::to_string(&host.storage.get(&key))?)
+        })
+    }
+
+    pub(crate) unsafe fn storage_set_raw(request: String) -> HostResult<()> {
+        MockHost::with(|host| {
+            host.record("storage_set_raw", request.clone())?;
+            let request: StorageSetRequest = serde_json::from_str(&request)?;
+            host.storage
+                .insert(request.get_key().clone(), request.get_value().clone());
+            Ok(())
+        })
+    }
+
+    pub(crate) unsafe fn storage_remove_raw(key: String) -> HostResult<()> {
+        MockHost::with(|host| {
+            host.record("storage_remove_raw", key.clone())?;
+            host.storage.remove(&key);
+            Ok(())
+        })
+    }
+
+    pub(crate) unsafe fn register_world_generator_raw(name: String) -> HostResult<()> {
+        MockHost::with(|host| {
+            host.record("register_world_generator_raw", name.clone())?;
+            host.world_generators.push(name);
+            Ok(())
+        })
+    }
+
+    pub(crate) unsafe fn get_plugin_slug_raw() -> HostResult<String> {
+        MockHost::with(|host| {
+            host.record("get_plugin_slug_raw", "")?;
+            Ok(host.plugin_slug.clone())
+        })
+    }
+
+    pub(crate) unsafe fn register_command_raw(command: String) -> HostResult<()> {
+        MockHost::with(|host| {
+            host.record("register_command_raw", command.clone())?;
+            let command: Command = serde_json::from_str(&command)?;
+            host.commands.push(command);
+            Ok(())
+        })
+    }
+
+    pub(crate) unsafe fn register_block_raw(manifest: String) -> HostResult<()> {
+        MockHost::with(|host| {
+            host.record("register_block_raw", manifest.clone())?;
+            let manifest: BlockTypeManifest = serde_json::from_str(&manifest)?;
+            let slug = host.plugin_slug.clone();
+            host.blocks
+                .register_block(&slug, &manifest)
+                .map_err(extism_pdk::Error::msg)
+        })
+    }
+
+    pub(crate) unsafe fn register_resource_raw(request: Vec<u8>) -> HostResult<()> {
+        MockHost::with(|host| {
+            let request = RegisterResourceRequest::decode(request).map_err(extism_pdk::Error::msg)?;
+            host.record("register_resource_raw", request.get_path().clone())?;
+            let slug = host.plugin_slug.clone();
+            host.blocks
+                .register_resource(&slug, request)
+                .map_err(extism_pdk::Error::msg)?;
+            Ok(())
+        })
+    }
+
+    pub(crate) unsafe fn get_plugin_config_raw() -> HostResult<String> {
+        MockHost::with(|host| {
+            host.record("get_plugin_config_raw", "")?;
+            Ok(host.config.clone())
+        })
+    }
+
+    pub(crate) unsafe fn schedule_task_raw(request: String) -> HostResult<u64> {
+        MockHost::with(|host| {
+            host.record("schedule_task_raw", request.clone())?;
+            let request: ScheduleTaskRequest = serde_json::from_str(&request)?;
+            let slug = host.plugin_slug.clone();
+            host.scheduler.schedule(&slug, request).map_err(extism_pdk::Error::msg)
+        })
+    }
+
+    pub(crate) unsafe fn cancel_task_raw(task_id: u64) -> HostResult<()> {
+        MockHost::with(|host| {
+            host.record("cancel_task_raw", task_id.to_string())?;
+            let slug = host.plugin_slug.clone();
+            host.scheduler.cancel(&slug, task_id).map_err(extism_pdk::Error::msg)
+        })
+    }
+
+    pub(crate) unsafe fn subscribe_raw(channel: String) -> HostResult<()> {
+        MockHost::with(|host| {
+            host.record("subscribe_raw", channel.clone())?;
+            host.subscriptions.insert(channel);
+            Ok(())
+        })
+    }
+
+    pub(crate) unsafe fn unsubscribe_raw(channel: String) -> HostResult<()> {
+        MockHost::with(|host| {
+            host.record("unsubscribe_raw", channel.clone())?;
+            host.subscriptions.remove(&channel);
+            Ok(())
+        })
+    }
+
+    pub(crate) unsafe fn publish_raw(request: String) -> HostResult<()> {
+        MockHost::with(|host| {
+            host.record("publish_raw", request.clone())?;
+            let request: PublishRequest = serde_json::from_str(&request)?;
+            host.published.push(request);
+            Ok(())
+        })
+    }
+
+    pub(crate) unsafe fn request_raw(request: String) -> HostResult<String> {
+        MockHost::with(|host| {
+            host.record("request_raw", request.clone())?;
+            let request: PluginRequest = serde_json::from_str(&request)?;
+            let key = (request.get_target().clone(), request.get_channel().clone());
+            let response = host.responses.get(&key).cloned().unwrap_or(PluginResponse::NotFound);
+            Ok(serde_json::to_string(&response)?)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MockHost;
+    use crate::{
+        chunks::{block_position::BlockPosition, chunk_data::BlockDataInfo, chunk_position::ChunkPosition},
+        plugin_api::{
+            logger::PluginLogger, manifest::PluginManifest, messaging::PluginMessenger, scheduler::PluginScheduler,
+            storage::PluginStorage, worlds_manager::WorldsManager,
+        },
+    };
+
+    #[test]
+    fn test_mock_host_world() {
+        MockHost::reset();
+        let worlds = WorldsManager;
+        assert!(!worlds.has_world("default").unwrap());
+        worlds.create_world("default").unwrap();
+        assert!(worlds.create_world("default").is_err());
+
+        let position = BlockPosition::new(-1, 20, 17);
+        worlds
+            .set_block("default", &position, Some(BlockDataInfo::create(3)))
+            .unwrap();
+        assert_eq!(
+            worlds.get_block("default", &position).unwrap(),
+            Some(BlockDataInfo::create(3))
+        );
+
+        let changed = worlds
+            .fill_region(
+                "default",
+                &BlockPosition::new(0, 0, 0),
+                &BlockPosition::new(1, 1, 1),
+                Some(BlockDataInfo::create(1)),
+            )
+            .unwrap();
+        assert_eq!(changed, 8);
+        let chunk = worlds.get_chunk("default", &ChunkPosition::new(0, 0)).unwrap().unwrap();
+        assert_eq!(
+            chunk.get_block_info(&BlockPosition::new(1, 1, 1)),
+            Some(BlockDataInfo::create(1))
+        );
+        assert!(worlds
+            .get_chunk("default", &ChunkPosition::new(5, 5))
+            .unwrap()
+            .is_none());
+
+        MockHost::with(|host| {
+            assert_eq!(host.get_calls_of("set_block_raw").len(), 1);
+            assert_eq!(host.get_calls_of("create_world_raw").len(), 2);
+        });
+    }
+
+    #[test]
+    fn test_mock_host_services() {
+        MockHost::reset();
+        let storage = PluginStorage;
+        storage.set("homes", &vec![1, 2]).unwrap();
+        assert_eq!(storage.get::<Vec<u32>>("homes").unwrap(), Some(vec![1, 2]));
+        storage.remove("homes").unwrap();
+        assert_eq!(storage.get::<Vec<u32>>("homes").unwrap(), None);
+
+        let scheduler = PluginScheduler;
+        let task_id = scheduler.schedule_delayed(2, &"warmup").unwrap();
+        assert!(MockHost::with(|host| host.tick()).is_empty());
+        let events = MockHost::with(|host| host.tick());
+        assert_eq!(events[0].get_task_id(), task_id);
+
+        let messenger = PluginMessenger;
+        messenger.publish("balance", &5).unwrap();
+        assert_eq!(MockHost::with(|host| host.get_published().len()), 1);
+
+        PluginLogger.warn("low balance");
+        MockHost::with(|host| assert_eq!(host.get_logs()[0], ("warn".to_string(), "low balance".to_string())));
+    }
+
+    #[test]
+    fn test_mock_host_capabilities() {
+        MockHost::reset();
+        let manifest =
+            PluginManifest::from_yaml("slug: reader\nversion: 1.0.0\napi_version: 1\ncapabilities: [world_read]")
+                .unwrap();
+        MockHost::with(|host| {
+            host.set_manifest(manifest);
+            host.create_world("default");
+        });
+
+        let worlds = WorldsManager;
+        assert!(worlds.has_world("default").unwrap());
+        let block = Some(BlockDataInfo::create(1));
+        assert!(worlds
+            .set_block("default", &BlockPosition::new(0, 0, 0), block)
+            .is_err());
+        assert!(PluginStorage.get::<u32>("key").is_err());
+    }
+}
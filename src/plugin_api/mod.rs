@@ -1,4 +1,24 @@
+pub mod custom_blocks;
 pub mod events;
+pub mod handler_result;
+pub mod harness;
+pub mod manifest;
+pub mod messaging;
+pub mod native;
+pub mod plugin_state;
+pub mod registry;
+pub mod scheduler;
+pub mod service;
+pub mod storage;
+pub mod world_access;
 
+#[cfg(feature = "wasm-plugin")]
+pub mod config;
+#[cfg(feature = "wasm-plugin")]
+pub mod context;
+#[cfg(feature = "wasm-plugin")]
+pub mod logger;
+#[cfg(feature = "mock-host")]
+pub mod mock_host;
 #[cfg(feature = "wasm-plugin")]
 pub mod worlds_manager;
@@ -0,0 +1,262 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::chunks::chunk_data::{ChunkData, WorldMacroData};
+
+use super::{
+    events::{
+        block_break::BlockBreakEvent,
+        block_interact::BlockInteractEvent,
+        block_place::BlockPlaceEvent,
+        chat_message::ChatMessageEvent,
+        chunk_populate::{ChunkPopulateEvent, ChunkPopulateResult},
+        command_complete::CommandCompleteEvent,
+        command_executed::CommandExecutedEvent,
+        generage_chunk::ChunkGenerateEvent,
+        generage_world_macro::GenerateWorldMacroEvent,
+        outcome::EventOutcome,
+        player_join::PlayerJoinEvent,
+        player_leave::PlayerLeaveEvent,
+        player_move::PlayerMoveEvent,
+        plugin_load::PluginLoadEvent,
+        plugin_message::{PluginMessageEvent, PluginRequestEvent},
+        plugin_unload::PluginUnloadEvent,
+        scheduled_task::ScheduledTaskEvent,
+        PluginEvent,
+    },
+    plugin_state::PluginState,
+};
+
+/// Plugin compiled into the server.
+///
+/// Mirrors the WASM event handlers: every method receives the same event
+/// and returns the same value as the `#[event_handler]` with the matching export name.
+/// Default implementations ignore the event.
+#[allow(unused_variables)]
+pub trait Plugin {
+    fn get_slug(&self) -> &str;
+
+    /// Version of the [`PluginState`] accepted by this instance on reload
+    fn get_state_version(&self) -> u32 {
+        0
+    }
+
+    /// Plugins with lower priority receive chained events like `on_chunk_populate` first
+    fn get_priority(&self) -> i32 {
+        0
+    }
+
+    fn on_plugin_load(&mut self, event: &PluginLoadEvent) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Returned state is handed over to the new instance on reload
+    fn on_plugin_unload(&mut self, event: &PluginUnloadEvent) -> Result<Option<PluginState>, String> {
+        Ok(None)
+    }
+
+    /// `None` if the plugin doesn't generate this world
+    fn on_chunk_generate(&mut self, event: &ChunkGenerateEvent) -> Result<Option<ChunkData>, String> {
+        Ok(None)
+    }
+
+    fn on_chunk_populate(&mut self, event: &ChunkPopulateEvent) -> Result<ChunkPopulateResult, String> {
+        Ok(ChunkPopulateResult::Unchanged)
+    }
+
+    fn on_generate_world_macro(&mut self, event: &GenerateWorldMacroEvent) -> Result<Option<WorldMacroData>, String> {
+        Ok(None)
+    }
+
+    fn on_player_join(&mut self, event: &PlayerJoinEvent) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn on_player_leave(&mut self, event: &PlayerLeaveEvent) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn on_player_move(&mut self, event: &PlayerMoveEvent) -> Result<EventOutcome, String> {
+        Ok(EventOutcome::Continue)
+    }
+
+    fn on_block_place(&mut self, event: &BlockPlaceEvent) -> Result<EventOutcome, String> {
+        Ok(EventOutcome::Continue)
+    }
+
+    fn on_block_break(&mut self, event: &BlockBreakEvent) -> Result<EventOutcome, String> {
+        Ok(EventOutcome::Continue)
+    }
+
+    fn on_block_interact(&mut self, event: &BlockInteractEvent) -> Result<EventOutcome, String> {
+        Ok(EventOutcome::Continue)
+    }
+
+    fn on_chat_message(&mut self, event: &ChatMessageEvent) -> Result<EventOutcome, String> {
+        Ok(EventOutcome::Continue)
+    }
+
+    fn on_command_executed(&mut self, event: &CommandExecutedEvent) -> Result<EventOutcome, String> {
+        Ok(EventOutcome::Continue)
+    }
+
+    fn on_command_complete(&mut self, event: &CommandCompleteEvent) -> Result<Vec<String>, String> {
+        Ok(Vec::new())
+    }
+
+    fn on_scheduled_task(&mut self, event: &ScheduledTaskEvent) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn on_plugin_message(&mut self, event: &PluginMessageEvent) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn on_plugin_request(&mut self, event: &PluginRequestEvent) -> Result<serde_json::Value, String> {
+        Ok(serde_json::Value::Null)
+    }
+}
+
+/// Event which can be sent to both native and WASM plugins.
+///
+/// `Output` is the handler return value; WASM handlers without return value produce the default.
+pub trait DispatchEvent: PluginEvent + Serialize {
+    type Output: Serialize + DeserializeOwned + Default;
+
+    fn call_native(&self, plugin: &mut dyn Plugin) -> Result<Self::Output, String>;
+}
+
+impl DispatchEvent for PluginLoadEvent {
+    type Output = ();
+
+    fn call_native(&self, plugin: &mut dyn Plugin) -> Result<Self::Output, String> {
+        plugin.on_plugin_load(self)
+    }
+}
+
+impl DispatchEvent for PluginUnloadEvent {
+    type Output = Option<PluginState>;
+
+    fn call_native(&self, plugin: &mut dyn Plugin) -> Result<Self::Output, String> {
+        plugin.on_plugin_unload(self)
+    }
+}
+
+impl DispatchEvent for ChunkGenerateEvent {
+    type Output = Option<ChunkData>;
+
+    fn call_native(&self, plugin: &mut dyn Plugin) -> Result<Self::Output, String> {
+        plugin.on_chunk_generate(self)
+    }
+}
+
+impl DispatchEvent for ChunkPopulateEvent {
+    type Output = ChunkPopulateResult;
+
+    fn call_native(&self, plugin: &mut dyn Plugin) -> Result<Self::Output, String> {
+        plugin.on_chunk_populate(self)
+    }
+}
+
+impl DispatchEvent for GenerateWorldMacroEvent {
+    type Output = Option<WorldMacroData>;
+
+    fn call_native(&self, plugin: &mut dyn Plugin) -> Result<Self::Output, String> {
+        plugin.on_generate_world_macro(self)
+    }
+}
+
+impl DispatchEvent for PlayerJoinEvent {
+    type Output = ();
+
+    fn call_native(&self, plugin: &mut dyn Plugin) -> Result<Self::Output, String> {
+        plugin.on_player_join(self)
+    }
+}
+
+impl DispatchEvent for PlayerLeaveEvent {
+    type Output = ();
+
+    fn call_native(&self, plugin: &mut dyn Plugin) -> Result<Self::Output, String> {
+        plugin.on_player_leave(self)
+    }
+}
+
+impl DispatchEvent for PlayerMoveEvent {
+    type Output = EventOutcome;
+
+    fn call_native(&self, plugin: &mut dyn Plugin) -> Result<Self::Output, String> {
+        plugin.on_player_move(self)
+    }
+}
+
+impl DispatchEvent for BlockPlaceEvent {
+    type Output = EventOutcome;
+
+    fn call_native(&self, plugin: &mut dyn Plugin) -> Result<Self::Output, String> {
+        plugin.on_block_place(self)
+    }
+}
+
+impl DispatchEvent for BlockBreakEvent {
+    type Output = EventOutcome;
+
+    fn call_native(&self, plugin: &mut dyn Plugin) -> Result<Self::Output, String> {
+        plugin.on_block_break(self)
+    }
+}
+
+impl DispatchEvent for BlockInteractEvent {
+    type Output = EventOutcome;
+
+    fn call_native(&self, plugin: &mut dyn Plugin) -> Result<Self::Output, String> {
+        plugin.on_block_interact(self)
+    }
+}
+
+impl DispatchEvent for ChatMessageEvent {
+    type Output = EventOutcome;
+
+    fn call_native(&self, plugin: &mut dyn Plugin) -> Result<Self::Output, String> {
+        plugin.on_chat_message(self)
+    }
+}
+
+impl DispatchEvent for CommandExecutedEvent {
+    type Output = EventOutcome;
+
+    fn call_native(&self, plugin: &mut dyn Plugin) -> Result<Self::Output, String> {
+        plugin.on_command_executed(self)
+    }
+}
+
+impl DispatchEvent for CommandCompleteEvent {
+    type Output = Vec<String>;
+
+    fn call_native(&self, plugin: &mut dyn Plugin) -> Result<Self::Output, String> {
+        plugin.on_command_complete(self)
+    }
+}
+
+impl DispatchEvent for ScheduledTaskEvent {
+    type Output = ();
+
+    fn call_native(&self, plugin: &mut dyn Plugin) -> Result<Self::Output, String> {
+        plugin.on_scheduled_task(self)
+    }
+}
+
+impl DispatchEvent for PluginMessageEvent {
+    type Output = ();
+
+    fn call_native(&self, plugin: &mut dyn Plugin) -> Result<Self::Output, String> {
+        plugin.on_plugin_message(self)
+    }
+}
+
+impl DispatchEvent for PluginRequestEvent {
+    type Output = serde_json::Value;
+
+    fn call_native(&self, plugin: &mut dyn Plugin) -> Result<Self::Output, String> {
+        plugin.on_plugin_request(self)
+    }
+}
@@ -0,0 +1,55 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// State of the plugin instance handed over to the new instance on reload.
+///
+/// The outgoing instance returns it from `on_plugin_unload`, the host passes it
+/// into `on_plugin_load` of the new instance only if the state versions are equal.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PluginState {
+    version: u32,
+    data: serde_json::Value,
+}
+
+impl PluginState {
+    pub fn create<T: Serialize>(version: u32, data: &T) -> Result<Self, String> {
+        let data = serde_json::to_value(data).map_err(|e| format!("&cplugin state serialize error: &4{}", e))?;
+        Ok(Self { version, data })
+    }
+
+    pub fn get_version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn get_data(&self) -> &serde_json::Value {
+        &self.data
+    }
+
+    pub fn get_data_as<T: DeserializeOwned>(&self) -> Result<T, String> {
+        serde_json::from_value(self.data.clone()).map_err(|e| format!("&cplugin state deserialize error: &4{}", e))
+    }
+
+    /// Host side check before the handoff
+    pub fn check_version(&self, version: u32) -> Result<(), String> {
+        if self.version != version {
+            return Err(format!(
+                "&cplugin state version &4{}&c is incompatible with &4{}",
+                self.version, version
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PluginState;
+
+    #[test]
+    fn test_plugin_state() {
+        let state = PluginState::create(2, &vec![("alice", 5)]).unwrap();
+        assert!(state.check_version(2).is_ok());
+        assert!(state.check_version(1).is_err());
+        assert_eq!(state.get_data_as::<Vec<(String, u32)>>().unwrap(), vec![("alice".to_string(), 5)]);
+        assert!(state.get_data_as::<u32>().is_err());
+    }
+}
@@ -0,0 +1,494 @@
+use serde::de::DeserializeOwned;
+
+use crate::chunks::{chunk_data::ChunkData, chunk_delta::BlockChange};
+
+use super::{
+    events::{
+        chunk_populate::{ChunkPopulateEvent, ChunkPopulateResult},
+        outcome::{CancellableEvent, EventOutcome},
+        plugin_load::PluginLoadEvent,
+        plugin_unload::{PluginUnloadEvent, UnloadReason},
+    },
+    handler_result::{parse_handler_output, HandlerError, HandlerErrorKind},
+    manifest::PluginManifest,
+    native::{DispatchEvent, Plugin},
+    plugin_state::PluginState,
+};
+
+/// Loaded WASM plugin, implemented by the server on top of the Extism instance
+pub trait WasmPlugin {
+    fn get_manifest(&self) -> &PluginManifest;
+
+    /// Calls the export with the json input and returns its raw output
+    fn call(&mut self, export_name: &str, input: &str) -> Result<String, String>;
+}
+
+enum RegisteredPlugin {
+    Native(Box<dyn Plugin>),
+    Wasm(Box<dyn WasmPlugin>),
+}
+
+impl RegisteredPlugin {
+    fn get_slug(&self) -> &str {
+        match self {
+            RegisteredPlugin::Native(p) => p.get_slug(),
+            RegisteredPlugin::Wasm(p) => p.get_manifest().get_slug(),
+        }
+    }
+
+    fn get_priority(&self) -> i32 {
+        match self {
+            RegisteredPlugin::Native(p) => p.get_priority(),
+            RegisteredPlugin::Wasm(p) => p.get_manifest().get_priority(),
+        }
+    }
+
+    fn get_state_version(&self) -> u32 {
+        match self {
+            RegisteredPlugin::Native(p) => p.get_state_version(),
+            RegisteredPlugin::Wasm(p) => p.get_manifest().get_state_version(),
+        }
+    }
+
+    fn load(&mut self, previous_state: Option<PluginState>) -> Result<(), String> {
+        match self.call(&PluginLoadEvent::create(previous_state)) {
+            Some(Err(e)) => Err(format!("&cplugin &4\"{}\" &cload error: {}", self.get_slug(), e)),
+            _ => Ok(()),
+        }
+    }
+
+    fn unload(&mut self, reason: UnloadReason) -> Result<Option<PluginState>, HandlerError> {
+        self.call(&PluginUnloadEvent::create(reason)).unwrap_or(Ok(None))
+    }
+
+    /// `None` if the WASM plugin didn't declare the event
+    fn call<E: DispatchEvent>(&mut self, event: &E) -> Option<Result<E::Output, HandlerError>> {
+        match self {
+            RegisteredPlugin::Native(plugin) => Some(
+                event
+                    .call_native(plugin.as_mut())
+                    .map_err(|e| HandlerError::create(HandlerErrorKind::Handler, e)),
+            ),
+            RegisteredPlugin::Wasm(plugin) => {
+                if !plugin.get_manifest().declares_event(E::EXPORT_NAME) {
+                    return None;
+                }
+                let input = match serde_json::to_string(event) {
+                    Ok(i) => i,
+                    Err(e) => return Some(Err(HandlerError::create(HandlerErrorKind::Serialize, e.to_string()))),
+                };
+                Some(call_wasm(plugin.as_mut(), E::EXPORT_NAME, &input))
+            }
+        }
+    }
+}
+
+fn call_wasm<T: DeserializeOwned + Default>(
+    plugin: &mut dyn WasmPlugin,
+    export_name: &str,
+    input: &str,
+) -> Result<T, HandlerError> {
+    let output = plugin
+        .call(export_name, input)
+        .map_err(|e| HandlerError::create(HandlerErrorKind::Output, e))?;
+    let value: Option<T> = parse_handler_output(&output)?;
+    Ok(value.unwrap_or_default())
+}
+
+/// Native and WASM plugins in the order of registration.
+///
+/// Events are sent to every native plugin and to WASM plugins which declared
+/// the event in their [`PluginManifest`].
+#[derive(Default)]
+pub struct PluginRegistry {
+    plugins: Vec<RegisteredPlugin>,
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the plugin and calls its `on_plugin_load`
+    pub fn register_native(&mut self, plugin: Box<dyn Plugin>) -> Result<(), String> {
+        self.register(RegisteredPlugin::Native(plugin))
+    }
+
+    /// Registers the plugin and calls its `on_plugin_load` export if declared
+    pub fn register_wasm(&mut self, plugin: Box<dyn WasmPlugin>) -> Result<(), String> {
+        self.register(RegisteredPlugin::Wasm(plugin))
+    }
+
+    fn register(&mut self, mut plugin: RegisteredPlugin) -> Result<(), String> {
+        if self.has_plugin(plugin.get_slug()) {
+            return Err(format!("&cplugin &4\"{}\" &cis already registered", plugin.get_slug()));
+        }
+        plugin.load(None)?;
+        self.plugins.push(plugin);
+        Ok(())
+    }
+
+    /// Replaces the running plugin with the new instance, see [`reload_wasm`](PluginRegistry::reload_wasm)
+    pub fn reload_native(&mut self, plugin: Box<dyn Plugin>) -> Result<bool, String> {
+        self.reload(RegisteredPlugin::Native(plugin))
+    }
+
+    /// Replaces the running plugin with the new instance.
+    ///
+    /// The state returned by `on_plugin_unload` of the old instance is passed into `on_plugin_load`
+    /// of the new one if the state versions match; returns true if the state was handed over.
+    /// If the new instance fails to load, the old one is loaded back with its state.
+    pub fn reload_wasm(&mut self, plugin: Box<dyn WasmPlugin>) -> Result<bool, String> {
+        self.reload(RegisteredPlugin::Wasm(plugin))
+    }
+
+    fn reload(&mut self, mut plugin: RegisteredPlugin) -> Result<bool, String> {
+        let slug = plugin.get_slug().to_string();
+        let Some(index) = self.plugins.iter().position(|p| p.get_slug() == slug) else {
+            return Err(format!("&cplugin &4\"{}\" &cis not registered", slug));
+        };
+
+        let state = match self.plugins[index].unload(UnloadReason::Reload) {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!(target: "plugins", "Plugin &e\"{}\"&r state is lost on reload: {}", slug, e);
+                None
+            }
+        };
+        let handoff_state = match state.as_ref() {
+            Some(s) => match s.check_version(plugin.get_state_version()) {
+                Ok(()) => Some(s.clone()),
+                Err(e) => {
+                    log::warn!(target: "plugins", "Plugin &e\"{}\"&r state is rejected: {}", slug, e);
+                    None
+                }
+            },
+            None => None,
+        };
+        let handed_over = handoff_state.is_some();
+
+        if let Err(e) = plugin.load(handoff_state) {
+            if let Err(e) = self.plugins[index].load(state) {
+                log::error!(target: "plugins", "Plugin &e\"{}\"&r rollback error: {}", slug, e);
+            }
+            return Err(e);
+        }
+        self.plugins[index] = plugin;
+        Ok(handed_over)
+    }
+
+    /// Sends [`PluginUnloadEvent`] and removes the plugin even if the handler failed
+    pub fn unregister(&mut self, slug: &str) -> Option<Result<(), HandlerError>> {
+        let index = self.plugins.iter().position(|p| p.get_slug() == slug)?;
+        let mut plugin = self.plugins.remove(index);
+        Some(plugin.unload(UnloadReason::Shutdown).map(|_| ()))
+    }
+
+    pub fn has_plugin(&self, slug: &str) -> bool {
+        self.plugins.iter().any(|p| p.get_slug() == slug)
+    }
+
+    pub fn get_slugs(&self) -> impl Iterator<Item = &str> {
+        self.plugins.iter().map(|p| p.get_slug())
+    }
+
+    /// Sends the event to every plugin which handles it
+    pub fn dispatch<E: DispatchEvent>(&mut self, event: &E) -> Vec<(String, Result<E::Output, HandlerError>)> {
+        self.plugins
+            .iter_mut()
+            .filter_map(|plugin| {
+                let result = plugin.call(event)?;
+                Some((plugin.get_slug().to_string(), result))
+            })
+            .collect()
+    }
+
+    /// Sends the event to a single plugin; `None` if the plugin isn't registered or doesn't handle the event
+    pub fn dispatch_to<E: DispatchEvent>(&mut self, slug: &str, event: &E) -> Option<Result<E::Output, HandlerError>> {
+        self.plugins.iter_mut().find(|p| p.get_slug() == slug)?.call(event)
+    }
+
+    /// Sends the cancellable event to every plugin; failed handlers don't cancel the action
+    pub fn dispatch_cancellable<E>(&mut self, event: &E) -> EventOutcome
+    where
+        E: DispatchEvent<Output = EventOutcome> + CancellableEvent,
+    {
+        let mut outcome = EventOutcome::Continue;
+        for (slug, result) in self.dispatch(event) {
+            match result {
+                Ok(o) => outcome = outcome.merge(o),
+                Err(e) => {
+                    log::error!(target: "plugins", "Plugin &e\"{}\"&r {} error: {}", slug, E::EXPORT_NAME, e);
+                }
+            }
+        }
+        outcome
+    }
+
+    /// Passes the generated chunk through `on_chunk_populate` of every plugin by priority.
+    ///
+    /// Failed handlers are skipped. Returns the populated chunk and the changes
+    /// which plugins made outside of it.
+    pub fn populate_chunk(&mut self, mut event: ChunkPopulateEvent) -> (ChunkData, Vec<BlockChange>) {
+        let mut order: Vec<usize> = (0..self.plugins.len()).collect();
+        order.sort_by_key(|i| self.plugins[*i].get_priority());
+
+        let mut outside = Vec::new();
+        for index in order {
+            let plugin = &mut self.plugins[index];
+            match plugin.call(&event) {
+                None | Some(Ok(ChunkPopulateResult::Unchanged)) => (),
+                Some(Ok(ChunkPopulateResult::Replace { chunk_data })) => {
+                    *event.get_chunk_data_mut() = chunk_data;
+                }
+                Some(Ok(ChunkPopulateResult::Delta { delta })) => {
+                    let chunk_position = *event.get_chunk_position();
+                    outside.extend(delta.apply(&chunk_position, event.get_chunk_data_mut()));
+                }
+                Some(Err(e)) => {
+                    log::error!(target: "plugins", "Plugin &e\"{}\"&r on_chunk_populate error: {}", plugin.get_slug(), e);
+                }
+            }
+        }
+        (event.take_chunk_data(), outside)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PluginRegistry, WasmPlugin};
+    use crate::plugin_api::{
+        events::{
+            chat_message::ChatMessageEvent, command_complete::CommandCompleteEvent, command_executed::CommandSender,
+            outcome::EventOutcome, player_info::PlayerInfo, player_join::PlayerJoinEvent, plugin_load::PluginLoadEvent,
+            plugin_unload::PluginUnloadEvent,
+        },
+        handler_result::HandlerResult,
+        manifest::PluginManifest,
+        native::Plugin,
+        plugin_state::PluginState,
+    };
+    use crate::{
+        chunks::{
+            block_position::BlockPosition,
+            chunk_data::{BlockDataInfo, ChunkData, ChunkSectionData},
+            chunk_delta::ChunkDelta,
+            chunk_position::ChunkPosition,
+        },
+        plugin_api::events::chunk_populate::{ChunkPopulateEvent, ChunkPopulateResult},
+        world_generator::traits::WorldGeneratorSettings,
+    };
+
+    struct Censor;
+
+    impl Plugin for Censor {
+        fn get_slug(&self) -> &str {
+            "censor"
+        }
+
+        fn on_chat_message(&mut self, event: &ChatMessageEvent) -> Result<EventOutcome, String> {
+            match event.get_message().contains("spam") {
+                true => Ok(EventOutcome::Cancel),
+                false => Ok(EventOutcome::Continue),
+            }
+        }
+    }
+
+    struct FakeWasm {
+        manifest: PluginManifest,
+    }
+
+    impl WasmPlugin for FakeWasm {
+        fn get_manifest(&self) -> &PluginManifest {
+            &self.manifest
+        }
+
+        fn call(&mut self, _export_name: &str, _input: &str) -> Result<String, String> {
+            let result = HandlerResult::ok(serde_json::Value::Null);
+            Ok(serde_json::to_string(&result).unwrap())
+        }
+    }
+
+    fn registry() -> PluginRegistry {
+        let manifest = PluginManifest::from_yaml(
+            "slug: greeter\nversion: 1.0.0\napi_version: 1\nevents: [on_plugin_load, on_player_join]",
+        )
+        .unwrap();
+        let mut registry = PluginRegistry::new();
+        registry.register_native(Box::new(Censor)).unwrap();
+        registry.register_wasm(Box::new(FakeWasm { manifest })).unwrap();
+        registry
+    }
+
+    #[test]
+    fn test_registry_dispatch() {
+        let mut registry = registry();
+        assert_eq!(registry.get_slugs().collect::<Vec<_>>(), vec!["censor", "greeter"]);
+        assert!(registry.register_native(Box::new(Censor)).is_err());
+
+        let player = PlayerInfo::create(1, "test");
+        let results = registry.dispatch(&PlayerJoinEvent::create(player.clone()));
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|(_, r)| r.is_ok()));
+
+        // Wasm plugin didn't declare the chat event
+        let results = registry.dispatch(&ChatMessageEvent::create(player.clone(), "hello"));
+        assert_eq!(results.len(), 1);
+
+        let outcome = registry.dispatch_cancellable(&ChatMessageEvent::create(player, "spam"));
+        assert!(outcome.is_cancelled());
+    }
+
+    #[test]
+    fn test_registry_unregister() {
+        let mut registry = registry();
+        assert!(registry.unregister("greeter").unwrap().is_ok());
+        assert!(registry.unregister("greeter").is_none());
+        assert!(!registry.has_plugin("greeter"));
+    }
+
+    struct Counter {
+        state_version: u32,
+        joins: u32,
+        fail_load: bool,
+    }
+
+    impl Counter {
+        fn create(state_version: u32) -> Self {
+            Self {
+                state_version,
+                joins: 0,
+                fail_load: false,
+            }
+        }
+    }
+
+    impl Plugin for Counter {
+        fn get_slug(&self) -> &str {
+            "counter"
+        }
+
+        fn get_state_version(&self) -> u32 {
+            self.state_version
+        }
+
+        fn on_plugin_load(&mut self, event: &PluginLoadEvent) -> Result<(), String> {
+            if self.fail_load {
+                return Err("broken build".to_string());
+            }
+            self.joins = event.get_previous_state_as(self.state_version)?.unwrap_or(0);
+            Ok(())
+        }
+
+        fn on_plugin_unload(&mut self, event: &PluginUnloadEvent) -> Result<Option<PluginState>, String> {
+            match event.is_reload() {
+                true => Ok(Some(PluginState::create(self.state_version, &self.joins)?)),
+                false => Ok(None),
+            }
+        }
+
+        fn on_player_join(&mut self, _event: &PlayerJoinEvent) -> Result<(), String> {
+            self.joins += 1;
+            Ok(())
+        }
+
+        fn on_command_complete(&mut self, _event: &CommandCompleteEvent) -> Result<Vec<String>, String> {
+            Ok(vec![self.joins.to_string()])
+        }
+    }
+
+    fn get_joins(registry: &mut PluginRegistry) -> String {
+        let event = CommandCompleteEvent::create(CommandSender::Console, "", "", "");
+        registry.dispatch_to("counter", &event).unwrap().unwrap().remove(0)
+    }
+
+    #[test]
+    fn test_registry_reload() {
+        let mut registry = PluginRegistry::new();
+        registry.register_native(Box::new(Counter::create(1))).unwrap();
+        registry.dispatch(&PlayerJoinEvent::create(PlayerInfo::create(1, "alice")));
+        registry.dispatch(&PlayerJoinEvent::create(PlayerInfo::create(2, "bob")));
+
+        assert!(registry.reload_native(Box::new(Counter::create(1))).unwrap());
+        assert_eq!(get_joins(&mut registry), "2");
+
+        // Failed instance is replaced back by the old one with its state
+        let mut broken = Counter::create(1);
+        broken.fail_load = true;
+        assert!(registry.reload_native(Box::new(broken)).is_err());
+        assert_eq!(get_joins(&mut registry), "2");
+
+        // Incompatible state is dropped
+        assert!(!registry.reload_native(Box::new(Counter::create(2))).unwrap());
+        assert_eq!(get_joins(&mut registry), "0");
+        assert!(registry
+            .reload_wasm(Box::new(FakeWasm {
+                manifest: PluginManifest::from_yaml("slug: other\nversion: 1.0.0\napi_version: 1").unwrap()
+            }))
+            .is_err());
+    }
+
+    struct Decorator {
+        slug: &'static str,
+        priority: i32,
+        block_id: u16,
+    }
+
+    impl Plugin for Decorator {
+        fn get_slug(&self) -> &str {
+            self.slug
+        }
+
+        fn get_priority(&self) -> i32 {
+            self.priority
+        }
+
+        fn on_chunk_populate(&mut self, event: &ChunkPopulateEvent) -> Result<ChunkPopulateResult, String> {
+            // Builds on top of the block placed by the previous plugin
+            let mut y = 0;
+            while event.get_block(&BlockPosition::new(0, y, 0)).is_some() {
+                y += 1;
+            }
+            let mut delta = ChunkDelta::new();
+            delta.set(BlockPosition::new(0, y, 0), Some(BlockDataInfo::create(self.block_id)));
+            delta.set(BlockPosition::new(-1, y, 0), Some(BlockDataInfo::create(self.block_id)));
+            Ok(ChunkPopulateResult::Delta { delta })
+        }
+    }
+
+    #[test]
+    fn test_registry_populate_chunk() {
+        let mut registry = PluginRegistry::new();
+        let ores = Decorator {
+            slug: "ores",
+            priority: 10,
+            block_id: 2,
+        };
+        let ruins = Decorator {
+            slug: "ruins",
+            priority: -5,
+            block_id: 1,
+        };
+        registry.register_native(Box::new(ores)).unwrap();
+        registry.register_native(Box::new(ruins)).unwrap();
+
+        let mut chunk_data = ChunkData::default();
+        chunk_data.push_section(ChunkSectionData::default());
+        let event = ChunkPopulateEvent::create(
+            ChunkPosition::new(0, 0),
+            WorldGeneratorSettings::default(),
+            chunk_data,
+            Vec::new(),
+        );
+        let (chunk_data, outside) = registry.populate_chunk(event);
+        assert_eq!(
+            chunk_data.get_block_info(&BlockPosition::new(0, 0, 0)),
+            Some(BlockDataInfo::create(1))
+        );
+        assert_eq!(
+            chunk_data.get_block_info(&BlockPosition::new(0, 1, 0)),
+            Some(BlockDataInfo::create(2))
+        );
+        assert_eq!(outside.len(), 2);
+    }
+}
@@ -0,0 +1,303 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
+};
+
+use super::events::scheduled_task::ScheduledTaskEvent;
+use crate::TARGET_TPS;
+
+/// Converts the duration into server ticks, rounding up
+pub fn duration_to_ticks(duration: Duration) -> u64 {
+    (duration.as_secs_f64() * TARGET_TPS).ceil() as u64
+}
+
+pub fn ticks_to_duration(ticks: u64) -> Duration {
+    Duration::from_secs_f64(ticks as f64 / TARGET_TPS)
+}
+
+/// Wire type of the task scheduling
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ScheduleTaskRequest {
+    delay_ticks: u64,
+    period_ticks: Option<u64>,
+    payload: serde_json::Value,
+}
+
+impl ScheduleTaskRequest {
+    /// Task runs once after `delay_ticks`, or every `period_ticks` after the delay
+    pub fn create(delay_ticks: u64, period_ticks: Option<u64>, payload: serde_json::Value) -> Self {
+        Self {
+            delay_ticks,
+            period_ticks,
+            payload,
+        }
+    }
+
+    pub fn get_delay_ticks(&self) -> u64 {
+        self.delay_ticks
+    }
+
+    pub fn get_period_ticks(&self) -> Option<u64> {
+        self.period_ticks
+    }
+
+    pub fn get_payload(&self) -> &serde_json::Value {
+        &self.payload
+    }
+}
+
+struct ScheduledTask {
+    plugin_slug: String,
+    period_ticks: Option<u64>,
+    payload: serde_json::Value,
+}
+
+/// Host side queue of the plugin tasks.
+///
+/// The server calls [`tick`](PluginTaskScheduler::tick) every tick and sends
+/// the returned events to the plugins.
+#[derive(Default)]
+pub struct PluginTaskScheduler {
+    current_tick: u64,
+    last_task_id: u64,
+    tasks: BTreeMap<u64, ScheduledTask>,
+    queue: BTreeSet<(u64, u64)>,
+}
+
+impl PluginTaskScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_current_tick(&self) -> u64 {
+        self.current_tick
+    }
+
+    pub fn schedule(&mut self, plugin_slug: &str, request: ScheduleTaskRequest) -> Result<u64, String> {
+        if request.period_ticks == Some(0) {
+            return Err("&ctask period must be at least one tick".to_string());
+        }
+        // Zero delay runs the task on the next tick
+        let due_tick = self.current_tick.checked_add(request.delay_ticks.max(1));
+        let next_tick = match request.period_ticks {
+            Some(period) => due_tick.and_then(|t| t.checked_add(period)),
+            None => due_tick,
+        };
+        let (Some(due_tick), Some(_)) = (due_tick, next_tick) else {
+            return Err("&ctask delay or period is too large".to_string());
+        };
+
+        self.last_task_id += 1;
+        let task_id = self.last_task_id;
+        self.queue.insert((due_tick, task_id));
+        self.tasks.insert(
+            task_id,
+            ScheduledTask {
+                plugin_slug: plugin_slug.to_string(),
+                period_ticks: request.period_ticks,
+                payload: request.payload,
+            },
+        );
+        Ok(task_id)
+    }
+
+    /// Plugins can cancel only their own tasks
+    pub fn cancel(&mut self, plugin_slug: &str, task_id: u64) -> Result<(), String> {
+        match self.tasks.get(&task_id) {
+            Some(task) if task.plugin_slug == plugin_slug => {
+                self.tasks.remove(&task_id);
+                Ok(())
+            }
+            _ => Err(format!("&ctask &4#{} &cnot found", task_id)),
+        }
+    }
+
+    /// Removes all tasks of the unloaded plugin
+    pub fn cancel_plugin(&mut self, plugin_slug: &str) {
+        self.tasks.retain(|_, task| task.plugin_slug != plugin_slug);
+    }
+
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Advances the scheduler by one tick and returns the due tasks with their plugin slugs
+    pub fn tick(&mut self) -> Vec<(String, ScheduledTaskEvent)> {
+        self.current_tick += 1;
+
+        let mut events = Vec::new();
+        while let Some(&(due_tick, task_id)) = self.queue.first() {
+            if due_tick > self.current_tick {
+                break;
+            }
+            self.queue.pop_first();
+
+            // Cancelled tasks are removed from the queue lazily
+            let Some(task) = self.tasks.get(&task_id) else {
+                continue;
+            };
+            events.push((
+                task.plugin_slug.clone(),
+                ScheduledTaskEvent::create(task_id, self.current_tick, task.payload.clone()),
+            ));
+            let next_tick = task.period_ticks.and_then(|p| self.current_tick.checked_add(p));
+            match next_tick {
+                Some(next_tick) => {
+                    self.queue.insert((next_tick, task_id));
+                }
+                None => {
+                    self.tasks.remove(&task_id);
+                }
+            }
+        }
+        events
+    }
+}
+
+#[cfg(feature = "wasm-plugin")]
+pub use self::plugin::PluginScheduler;
+
+#[cfg(feature = "wasm-plugin")]
+mod plugin {
+    use serde::Serialize;
+    use std::time::Duration;
+
+    use super::{duration_to_ticks, ScheduleTaskRequest};
+    use crate::plugin_api::service::PluginService;
+
+    #[cfg(not(feature = "mock-host"))]
+    #[extism_pdk::host_fn]
+    extern "ExtismHost" {
+        fn schedule_task_raw(request: String) -> u64;
+        fn cancel_task_raw(task_id: u64) -> ();
+    }
+
+    #[cfg(feature = "mock-host")]
+    use crate::plugin_api::mock_host::host::{cancel_task_raw, schedule_task_raw};
+
+    /// Delayed and repeating tasks of the plugin.
+    ///
+    /// Tasks are delivered as [`ScheduledTaskEvent`](crate::plugin_api::events::scheduled_task::ScheduledTaskEvent)
+    /// with the given payload.
+    #[derive(Default)]
+    pub struct PluginScheduler;
+
+    impl PluginService for PluginScheduler {
+        fn inject() -> Self {
+            Self
+        }
+    }
+
+    impl PluginScheduler {
+        pub fn schedule_delayed<T: Serialize>(&self, delay_ticks: u64, payload: &T) -> Result<u64, extism_pdk::Error> {
+            self.schedule(ScheduleTaskRequest::create(
+                delay_ticks,
+                None,
+                serde_json::to_value(payload)?,
+            ))
+        }
+
+        pub fn schedule_repeating<T: Serialize>(
+            &self,
+            delay_ticks: u64,
+            period_ticks: u64,
+            payload: &T,
+        ) -> Result<u64, extism_pdk::Error> {
+            self.schedule(ScheduleTaskRequest::create(
+                delay_ticks,
+                Some(period_ticks),
+                serde_json::to_value(payload)?,
+            ))
+        }
+
+        pub fn schedule_after<T: Serialize>(&self, delay: Duration, payload: &T) -> Result<u64, extism_pdk::Error> {
+            self.schedule_delayed(duration_to_ticks(delay), payload)
+        }
+
+        pub fn cancel(&self, task_id: u64) -> Result<(), extism_pdk::Error> {
+            unsafe { cancel_task_raw(task_id) }
+        }
+
+        fn schedule(&self, request: ScheduleTaskRequest) -> Result<u64, extism_pdk::Error> {
+            unsafe { schedule_task_raw(serde_json::to_string(&request)?) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{duration_to_ticks, PluginTaskScheduler, ScheduleTaskRequest};
+
+    #[test]
+    fn test_duration_to_ticks() {
+        assert_eq!(duration_to_ticks(Duration::from_secs(1)), 64);
+        assert_eq!(duration_to_ticks(Duration::from_millis(10)), 1);
+        assert_eq!(duration_to_ticks(Duration::ZERO), 0);
+    }
+
+    #[test]
+    fn test_scheduler_tasks() {
+        let mut scheduler = PluginTaskScheduler::new();
+        let once = scheduler
+            .schedule("a", ScheduleTaskRequest::create(2, None, serde_json::json!("once")))
+            .unwrap();
+        let repeating = scheduler
+            .schedule("b", ScheduleTaskRequest::create(1, Some(2), serde_json::json!(5)))
+            .unwrap();
+
+        let mut runs = Vec::new();
+        for _ in 0..5 {
+            for (slug, event) in scheduler.tick() {
+                runs.push((slug, event.get_task_id(), event.get_tick()));
+            }
+        }
+        assert_eq!(
+            runs,
+            vec![
+                ("b".to_string(), repeating, 1),
+                ("a".to_string(), once, 2),
+                ("b".to_string(), repeating, 3),
+                ("b".to_string(), repeating, 5),
+            ]
+        );
+        assert_eq!(scheduler.len(), 1);
+    }
+
+    #[test]
+    fn test_scheduler_cancel() {
+        let mut scheduler = PluginTaskScheduler::new();
+        let task_id = scheduler
+            .schedule("a", ScheduleTaskRequest::create(1, Some(1), serde_json::Value::Null))
+            .unwrap();
+        assert!(scheduler.cancel("b", task_id).is_err());
+        assert!(scheduler.cancel("a", task_id).is_ok());
+        assert!(scheduler.tick().is_empty());
+
+        scheduler
+            .schedule("a", ScheduleTaskRequest::create(0, None, serde_json::Value::Null))
+            .unwrap();
+        scheduler.cancel_plugin("a");
+        assert!(scheduler.is_empty());
+        assert!(scheduler
+            .schedule("a", ScheduleTaskRequest::create(0, Some(0), serde_json::Value::Null))
+            .is_err());
+    }
+
+    #[test]
+    fn test_scheduler_overflow() {
+        let mut scheduler = PluginTaskScheduler::new();
+        scheduler.tick();
+        let request = ScheduleTaskRequest::create(u64::MAX, None, serde_json::Value::Null);
+        assert!(scheduler.schedule("a", request).is_err());
+        let request = ScheduleTaskRequest::create(1, Some(u64::MAX), serde_json::Value::Null);
+        assert!(scheduler.schedule("a", request).is_err());
+        assert!(scheduler.is_empty());
+    }
+}
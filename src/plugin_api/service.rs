@@ -0,0 +1,16 @@
+/// Host service which can be passed to an [`event_handler`](crate::event_handler) as an extra argument.
+///
+/// ```ignore
+/// #[event_handler]
+/// pub fn on_plugin_load(event: PluginLoadEvent, worlds: WorldsManager, logger: PluginLogger) -> Result<(), Error> {
+///     logger.info("loaded");
+///     Ok(())
+/// }
+/// ```
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be injected into the event handler",
+    note = "extra event handler arguments must implement `PluginService`"
+)]
+pub trait PluginService: Sized {
+    fn inject() -> Self;
+}
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+/// Wire type of the plugin storage write
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StorageSetRequest {
+    key: String,
+    value: serde_json::Value,
+}
+
+impl StorageSetRequest {
+    pub fn create(key: impl Into<String>, value: serde_json::Value) -> Self {
+        Self { key: key.into(), value }
+    }
+
+    pub fn get_key(&self) -> &String {
+        &self.key
+    }
+
+    pub fn get_value(&self) -> &serde_json::Value {
+        &self.value
+    }
+}
+
+#[cfg(feature = "wasm-plugin")]
+pub use self::plugin::PluginStorage;
+
+#[cfg(feature = "wasm-plugin")]
+mod plugin {
+    use serde::{de::DeserializeOwned, Serialize};
+
+    use super::StorageSetRequest;
+    use crate::plugin_api::service::PluginService;
+
+    #[cfg(not(feature = "mock-host"))]
+    #[extism_pdk::host_fn]
+    extern "ExtismHost" {
+        fn storage_get_raw(key: String) -> String;
+        fn storage_set_raw(request: String) -> ();
+        fn storage_remove_raw(key: String) -> ();
+    }
+
+    #[cfg(feature = "mock-host")]
+    use crate::plugin_api::mock_host::host::{storage_get_raw, storage_remove_raw, storage_set_raw};
+
+    /// Persistent key-value storage of the plugin, kept by the server between restarts
+    #[derive(Default)]
+    pub struct PluginStorage;
+
+    impl PluginService for PluginStorage {
+        fn inject() -> Self {
+            Self
+        }
+    }
+
+    impl PluginStorage {
+        pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, extism_pdk::Error> {
+            let result = unsafe { storage_get_raw(key.to_string())? };
+            let value: Option<serde_json::Value> = serde_json::from_str(&result)?;
+            match value {
+                Some(v) => Ok(Some(serde_json::from_value(v)?)),
+                None => Ok(None),
+            }
+        }
+
+        pub fn set<T: Serialize>(&self, key: &str, value: &T) -> Result<(), extism_pdk::Error> {
+            let request = StorageSetRequest::create(key, serde_json::to_value(value)?);
+            unsafe { storage_set_raw(serde_json::to_string(&request)?) }
+        }
+
+        pub fn remove(&self, key: &str) -> Result<(), extism_pdk::Error> {
+            unsafe { storage_remove_raw(key.to_string()) }
+        }
+    }
+}
@@ -0,0 +1,279 @@
+//! Wire types of the block access host functions.
+//!
+//! Requests are sent to the host as json strings, chunks are returned
+//! as [`Compressable`] bytes because of their size.
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    chunks::{
+        block_position::BlockPosition, chunk_data::BlockDataInfo, chunk_data::ChunkData, chunk_position::ChunkPosition,
+    },
+    utils::compressable::Compressable,
+};
+
+/// Maximum number of blocks changed by a single fill request
+pub const MAX_FILL_VOLUME: u64 = 64 * 64 * 64;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GetBlockRequest {
+    world_slug: String,
+    position: BlockPosition,
+}
+
+impl GetBlockRequest {
+    pub fn create(world_slug: impl Into<String>, position: BlockPosition) -> Self {
+        Self {
+            world_slug: world_slug.into(),
+            position,
+        }
+    }
+
+    pub fn get_world_slug(&self) -> &String {
+        &self.world_slug
+    }
+
+    pub fn get_position(&self) -> &BlockPosition {
+        &self.position
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SetBlockRequest {
+    world_slug: String,
+    position: BlockPosition,
+    block: Option<BlockDataInfo>,
+}
+
+impl SetBlockRequest {
+    /// `None` block removes the block
+    pub fn create(world_slug: impl Into<String>, position: BlockPosition, block: Option<BlockDataInfo>) -> Self {
+        Self {
+            world_slug: world_slug.into(),
+            position,
+            block,
+        }
+    }
+
+    pub fn get_world_slug(&self) -> &String {
+        &self.world_slug
+    }
+
+    pub fn get_position(&self) -> &BlockPosition {
+        &self.position
+    }
+
+    pub fn get_block(&self) -> &Option<BlockDataInfo> {
+        &self.block
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FillRegionRequest {
+    world_slug: String,
+    from: BlockPosition,
+    to: BlockPosition,
+    block: Option<BlockDataInfo>,
+}
+
+impl FillRegionRequest {
+    /// Both corners are inclusive; `None` block clears the region
+    pub fn create(
+        world_slug: impl Into<String>,
+        from: BlockPosition,
+        to: BlockPosition,
+        block: Option<BlockDataInfo>,
+    ) -> Self {
+        Self {
+            world_slug: world_slug.into(),
+            from,
+            to,
+            block,
+        }
+    }
+
+    pub fn get_world_slug(&self) -> &String {
+        &self.world_slug
+    }
+
+    pub fn get_block(&self) -> &Option<BlockDataInfo> {
+        &self.block
+    }
+
+    pub fn get_min(&self) -> BlockPosition {
+        BlockPosition::new(
+            self.from.get_x().min(self.to.get_x()),
+            self.from.get_y().min(self.to.get_y()),
+            self.from.get_z().min(self.to.get_z()),
+        )
+    }
+
+    pub fn get_max(&self) -> BlockPosition {
+        BlockPosition::new(
+            self.from.get_x().max(self.to.get_x()),
+            self.from.get_y().max(self.to.get_y()),
+            self.from.get_z().max(self.to.get_z()),
+        )
+    }
+
+    /// `None` if the volume doesn't fit into u64
+    pub fn get_volume(&self) -> Option<u64> {
+        let (min, max) = (self.get_min(), self.get_max());
+        let length = |min: i64, max: i64| max.checked_sub(min)?.checked_add(1).map(|l| l as u64);
+        length(min.get_x(), max.get_x())?
+            .checked_mul(length(min.get_y(), max.get_y())?)?
+            .checked_mul(length(min.get_z(), max.get_z())?)
+    }
+
+    /// Host must call it before filling
+    pub fn validate(&self) -> Result<(), String> {
+        let (min, max) = (self.get_min(), self.get_max());
+        let max_y = crate::VERTICAL_SECTIONS as i64 * crate::CHUNK_SIZE as i64;
+        if min.get_y() < 0 || max.get_y() >= max_y {
+            return Err(format!(
+                "Fill region y &e{}..{}&r is outside of the world height &e0..{}",
+                min.get_y(),
+                max.get_y(),
+                max_y - 1
+            ));
+        }
+        match self.get_volume() {
+            Some(volume) if volume <= MAX_FILL_VOLUME => (),
+            Some(volume) => {
+                return Err(format!(
+                    "Fill region volume &e{}&r is larger than maximum &e{}",
+                    volume, MAX_FILL_VOLUME
+                ));
+            }
+            None => {
+                return Err(format!(
+                    "Fill region volume is larger than maximum &e{}",
+                    MAX_FILL_VOLUME
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn iter_positions(&self) -> impl Iterator<Item = BlockPosition> {
+        let (min, max) = (self.get_min(), self.get_max());
+        (min.get_y()..=max.get_y()).flat_map(move |y| {
+            (min.get_z()..=max.get_z())
+                .flat_map(move |z| (min.get_x()..=max.get_x()).map(move |x| BlockPosition::new(x, y, z)))
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GetChunkRequest {
+    world_slug: String,
+    chunk_position: ChunkPosition,
+}
+
+impl GetChunkRequest {
+    pub fn create(world_slug: impl Into<String>, chunk_position: ChunkPosition) -> Self {
+        Self {
+            world_slug: world_slug.into(),
+            chunk_position,
+        }
+    }
+
+    pub fn get_world_slug(&self) -> &String {
+        &self.world_slug
+    }
+
+    pub fn get_chunk_position(&self) -> &ChunkPosition {
+        &self.chunk_position
+    }
+}
+
+/// `None` if the chunk isn't loaded
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ChunkReadResponse {
+    chunk_data: Option<ChunkData>,
+}
+
+impl Compressable for ChunkReadResponse {}
+
+impl ChunkReadResponse {
+    pub fn create(chunk_data: Option<ChunkData>) -> Self {
+        Self { chunk_data }
+    }
+
+    pub fn get_chunk_data(&self) -> &Option<ChunkData> {
+        &self.chunk_data
+    }
+
+    pub fn take_chunk_data(self) -> Option<ChunkData> {
+        self.chunk_data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChunkReadResponse, FillRegionRequest, MAX_FILL_VOLUME};
+    use crate::{
+        chunks::{
+            block_position::BlockPosition,
+            chunk_data::{BlockDataInfo, ChunkData, ChunkSectionData},
+        },
+        utils::compressable::Compressable,
+    };
+
+    #[test]
+    fn test_fill_region() {
+        let request = FillRegionRequest::create(
+            "world",
+            BlockPosition::new(2, 5, -1),
+            BlockPosition::new(0, 4, 0),
+            Some(BlockDataInfo::create(1)),
+        );
+        assert_eq!(request.get_min(), BlockPosition::new(0, 4, -1));
+        assert_eq!(request.get_volume(), Some(12));
+        assert_eq!(request.iter_positions().count(), 12);
+        assert!(request.validate().is_ok());
+
+        let json = serde_json::to_string(&request).unwrap();
+        let request: FillRegionRequest = serde_json::from_str(&json).unwrap();
+        assert_eq!(request.get_block(), &Some(BlockDataInfo::create(1)));
+    }
+
+    #[test]
+    fn test_fill_region_validate() {
+        let request =
+            FillRegionRequest::create("world", BlockPosition::new(0, -1, 0), BlockPosition::new(0, 0, 0), None);
+        assert!(request.validate().is_err());
+
+        let request = FillRegionRequest::create(
+            "world",
+            BlockPosition::new(0, 0, 0),
+            BlockPosition::new(1000, 10, 0),
+            None,
+        );
+        assert!(request.get_volume().unwrap() <= MAX_FILL_VOLUME);
+        let request = FillRegionRequest::create(
+            "world",
+            BlockPosition::new(0, 0, 0),
+            BlockPosition::new(1000, 100, 10),
+            None,
+        );
+        assert!(request.validate().is_err());
+
+        let request = FillRegionRequest::create(
+            "world",
+            BlockPosition::new(i64::MIN, 0, i64::MIN),
+            BlockPosition::new(i64::MAX, 10, i64::MAX),
+            None,
+        );
+        assert_eq!(request.get_volume(), None);
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn test_chunk_read_response() {
+        let mut chunk_data = ChunkData::default();
+        chunk_data.push_section(ChunkSectionData::default());
+        let encoded = ChunkReadResponse::create(Some(chunk_data)).encode();
+        let response = ChunkReadResponse::decode(encoded).unwrap();
+        assert_eq!(response.take_chunk_data().unwrap().len(), 1);
+    }
+}
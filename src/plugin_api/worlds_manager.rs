@@ -1,11 +1,38 @@
+use crate::{
+    chunks::{
+        block_position::BlockPosition, chunk_data::BlockDataInfo, chunk_data::ChunkData, chunk_position::ChunkPosition,
+    },
+    utils::compressable::Compressable,
+};
+
+use super::{
+    service::PluginService,
+    world_access::{ChunkReadResponse, FillRegionRequest, GetBlockRequest, GetChunkRequest, SetBlockRequest},
+};
 
 #[derive(Default)]
 pub struct WorldsManager;
 
+#[cfg(not(feature = "mock-host"))]
 #[extism_pdk::host_fn]
 extern "ExtismHost" {
     fn has_world_raw(slug: String) -> String;
     fn create_world_raw(slug: String) -> ();
+    fn get_block_raw(request: String) -> String;
+    fn set_block_raw(request: String) -> ();
+    fn fill_region_raw(request: String) -> String;
+    fn get_chunk_raw(request: String) -> Vec<u8>;
+}
+
+#[cfg(feature = "mock-host")]
+use crate::plugin_api::mock_host::host::{
+    create_world_raw, fill_region_raw, get_block_raw, get_chunk_raw, has_world_raw, set_block_raw,
+};
+
+impl PluginService for WorldsManager {
+    fn inject() -> Self {
+        Self
+    }
 }
 
 impl WorldsManager {
@@ -17,4 +44,48 @@ impl WorldsManager {
     pub fn create_world(&self, slug: &str) -> Result<(), extism_pdk::Error> {
         unsafe { create_world_raw(slug.to_string()) }
     }
+
+    /// Returns `None` for air and for blocks of unloaded chunks
+    pub fn get_block(&self, slug: &str, position: &BlockPosition) -> Result<Option<BlockDataInfo>, extism_pdk::Error> {
+        let request = serde_json::to_string(&GetBlockRequest::create(slug, *position))?;
+        let result = unsafe { get_block_raw(request)? };
+        Ok(serde_json::from_str(&result)?)
+    }
+
+    /// `None` block removes the block
+    pub fn set_block(
+        &self,
+        slug: &str,
+        position: &BlockPosition,
+        block: Option<BlockDataInfo>,
+    ) -> Result<(), extism_pdk::Error> {
+        let request = serde_json::to_string(&SetBlockRequest::create(slug, *position, block))?;
+        unsafe { set_block_raw(request) }
+    }
+
+    /// Fills the region between two corners (inclusive) and returns the number of changed blocks.
+    pub fn fill_region(
+        &self,
+        slug: &str,
+        from: &BlockPosition,
+        to: &BlockPosition,
+        block: Option<BlockDataInfo>,
+    ) -> Result<u64, extism_pdk::Error> {
+        let request = FillRegionRequest::create(slug, *from, *to, block);
+        request.validate().map_err(extism_pdk::Error::msg)?;
+        let result = unsafe { fill_region_raw(serde_json::to_string(&request)?)? };
+        Ok(serde_json::from_str(&result)?)
+    }
+
+    /// Returns `None` if the chunk isn't loaded
+    pub fn get_chunk(
+        &self,
+        slug: &str,
+        chunk_position: &ChunkPosition,
+    ) -> Result<Option<ChunkData>, extism_pdk::Error> {
+        let request = serde_json::to_string(&GetChunkRequest::create(slug, *chunk_position))?;
+        let result = unsafe { get_chunk_raw(request)? };
+        let response = ChunkReadResponse::decode(result).map_err(extism_pdk::Error::msg)?;
+        Ok(response.take_chunk_data())
+    }
 }
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+use super::{source::ResourceSource, uri::ResourceUri};
+
+const REGEX_PACK_SLUG: &str = r"^[a-z0-9_-]{2,32}$";
+
+/// Hex encoded sha256 of the resource data
+pub fn hash_resource(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ResourceAsset {
+    hash: String,
+    size: u64,
+}
+
+impl ResourceAsset {
+    pub fn from_data(data: &[u8]) -> Self {
+        Self {
+            hash: hash_resource(data),
+            size: data.len() as u64,
+        }
+    }
+
+    pub fn get_hash(&self) -> &String {
+        &self.hash
+    }
+
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+}
+
+/// List of the pack assets with their hashes.
+///
+/// ```yaml
+/// slug: hd-blocks
+/// priority: 10
+/// assets:
+///   default://assets/block/dirt.png:
+///     hash: 9f86d08...
+///     size: 512
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ResourcePackManifest {
+    slug: String,
+
+    /// Packs with higher priority override assets of lower ones
+    #[serde(default)]
+    priority: i32,
+
+    #[serde(default)]
+    assets: BTreeMap<ResourceUri, ResourceAsset>,
+}
+
+impl ResourcePackManifest {
+    pub fn create(slug: impl Into<String>, priority: i32) -> Result<Self, String> {
+        let manifest = Self {
+            slug: slug.into(),
+            priority,
+            assets: Default::default(),
+        };
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// Hashes every resource of the source
+    pub fn build(slug: impl Into<String>, priority: i32, source: &dyn ResourceSource) -> Result<Self, String> {
+        let mut manifest = Self::create(slug, priority)?;
+        for uri in source.list()? {
+            let Some(data) = source.read(&uri)? else {
+                return Err(format!("&cresource &4\"{}\" &cis listed but can't be read", uri));
+            };
+            manifest.assets.insert(uri, ResourceAsset::from_data(&data));
+        }
+        Ok(manifest)
+    }
+
+    pub fn from_yaml(yaml: &str) -> Result<Self, String> {
+        let manifest: Self =
+            serde_yaml::from_str(yaml).map_err(|e| format!("&cresource pack manifest error: &4{}", e))?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    pub fn to_yaml(&self) -> String {
+        serde_yaml::to_string(self).unwrap()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let re = regex::Regex::new(REGEX_PACK_SLUG).unwrap();
+        if !re.is_match(&self.slug) {
+            return Err(format!("&cresource pack slug &4\"{}\" &cis invalid", self.slug));
+        }
+        Ok(())
+    }
+
+    /// Checks that the source contains every asset with the same hash
+    pub fn verify(&self, source: &dyn ResourceSource) -> Result<(), String> {
+        for (uri, asset) in self.assets.iter() {
+            let Some(data) = source.read(uri)? else {
+                return Err(format!(
+                    "&cresource pack &4\"{}\" &casset &4\"{}\" &cis missing",
+                    self.slug, uri
+                ));
+            };
+            if hash_resource(&data) != asset.hash {
+                return Err(format!(
+                    "&cresource pack &4\"{}\" &casset &4\"{}\" &chash doesn't match",
+                    self.slug, uri
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_slug(&self) -> &String {
+        &self.slug
+    }
+
+    pub fn get_priority(&self) -> i32 {
+        self.priority
+    }
+
+    pub fn get_assets(&self) -> &BTreeMap<ResourceUri, ResourceAsset> {
+        &self.assets
+    }
+
+    pub fn get_asset(&self, uri: &ResourceUri) -> Option<&ResourceAsset> {
+        self.assets.get(uri)
+    }
+}
@@ -0,0 +1,4 @@
+pub mod manifest;
+pub mod source;
+pub mod stack;
+pub mod uri;
@@ -0,0 +1,162 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use super::uri::ResourceUri;
+
+/// Storage of the resource pack files
+pub trait ResourceSource {
+    fn list(&self) -> Result<Vec<ResourceUri>, String>;
+
+    /// `None` if the source doesn't contain the resource
+    fn read(&self, uri: &ResourceUri) -> Result<Option<Vec<u8>>, String>;
+}
+
+/// Files inside the directory as "<root>/<namespace>/<path>"
+pub struct DirectorySource {
+    root: PathBuf,
+}
+
+impl DirectorySource {
+    pub fn create(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn list_dir(&self, namespace: &str, dir: &Path, result: &mut Vec<ResourceUri>) -> Result<(), String> {
+        let entries = std::fs::read_dir(dir).map_err(|e| format!("&cresource dir &4{:?} &cread error: {}", dir, e))?;
+        for entry in entries {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            if path.is_dir() {
+                self.list_dir(namespace, &path, result)?;
+                continue;
+            }
+            let relative = path.strip_prefix(self.root.join(namespace)).unwrap();
+            let relative: Vec<String> = relative.iter().map(|p| p.to_string_lossy().to_string()).collect();
+            // Stray files like ".DS_Store" are not resources
+            if let Ok(uri) = ResourceUri::create(namespace, relative.join("/")) {
+                result.push(uri);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ResourceSource for DirectorySource {
+    fn list(&self) -> Result<Vec<ResourceUri>, String> {
+        let mut result = Vec::new();
+        let entries = std::fs::read_dir(&self.root)
+            .map_err(|e| format!("&cresource dir &4{:?} &cread error: {}", self.root, e))?;
+        for entry in entries {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let namespace = path.file_name().unwrap().to_string_lossy().to_string();
+            // Directories like ".git" are not namespaces
+            if !ResourceUri::is_valid_namespace(&namespace) {
+                continue;
+            }
+            self.list_dir(&namespace, &path, &mut result)?;
+        }
+        result.sort();
+        Ok(result)
+    }
+
+    fn read(&self, uri: &ResourceUri) -> Result<Option<Vec<u8>>, String> {
+        let path = self.root.join(uri.get_namespace()).join(uri.get_path());
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let data = std::fs::read(&path).map_err(|e| format!("&cresource &4\"{}\" &cread error: {}", uri, e))?;
+        Ok(Some(data))
+    }
+}
+
+/// Resources kept in memory, like the ones registered by plugins
+#[derive(Default)]
+pub struct MemorySource {
+    resources: BTreeMap<ResourceUri, Vec<u8>>,
+}
+
+impl MemorySource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, uri: ResourceUri, data: Vec<u8>) {
+        self.resources.insert(uri, data);
+    }
+}
+
+impl ResourceSource for MemorySource {
+    fn list(&self) -> Result<Vec<ResourceUri>, String> {
+        Ok(self.resources.keys().cloned().collect())
+    }
+
+    fn read(&self, uri: &ResourceUri) -> Result<Option<Vec<u8>>, String> {
+        Ok(self.resources.get(uri).cloned())
+    }
+}
+
+#[cfg(feature = "full")]
+pub use self::zip_source::ZipSource;
+
+#[cfg(feature = "full")]
+mod zip_source {
+    use std::{fs::File, io::Read, path::Path, sync::Mutex};
+
+    use super::ResourceSource;
+    use crate::resource_packs::uri::ResourceUri;
+
+    /// Zip archive with the same layout as [`DirectorySource`](super::DirectorySource)
+    pub struct ZipSource {
+        archive: Mutex<zip::ZipArchive<File>>,
+    }
+
+    impl ZipSource {
+        pub fn open(path: &Path) -> Result<Self, String> {
+            let file = File::open(path).map_err(|e| format!("&cresource pack &4{:?} &copen error: {}", path, e))?;
+            let archive =
+                zip::ZipArchive::new(file).map_err(|e| format!("&cresource pack &4{:?} &cread error: {}", path, e))?;
+            Ok(Self {
+                archive: Mutex::new(archive),
+            })
+        }
+    }
+
+    impl ResourceSource for ZipSource {
+        fn list(&self) -> Result<Vec<ResourceUri>, String> {
+            let archive = self.archive.lock().unwrap();
+            let mut result = Vec::new();
+            for name in archive.file_names() {
+                if name.ends_with('/') {
+                    continue;
+                }
+                let Some((namespace, path)) = name.split_once('/') else {
+                    continue;
+                };
+                // Entries like "__MACOSX/" are not resources
+                if let Ok(uri) = ResourceUri::create(namespace, path) {
+                    result.push(uri);
+                }
+            }
+            result.sort();
+            Ok(result)
+        }
+
+        fn read(&self, uri: &ResourceUri) -> Result<Option<Vec<u8>>, String> {
+            let mut archive = self.archive.lock().unwrap();
+            let name = format!("{}/{}", uri.get_namespace(), uri.get_path());
+            let mut file = match archive.by_name(&name) {
+                Ok(f) => f,
+                Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+                Err(e) => return Err(format!("&cresource &4\"{}\" &cread error: {}", uri, e)),
+            };
+            let mut data = Vec::with_capacity(file.size() as usize);
+            file.read_to_end(&mut data)
+                .map_err(|e| format!("&cresource &4\"{}\" &cread error: {}", uri, e))?;
+            Ok(Some(data))
+        }
+    }
+}
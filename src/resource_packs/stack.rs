@@ -0,0 +1,268 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::{
+    manifest::{hash_resource, ResourceAsset, ResourcePackManifest},
+    source::ResourceSource,
+    uri::ResourceUri,
+};
+
+pub struct ResourcePack {
+    manifest: ResourcePackManifest,
+    source: Box<dyn ResourceSource>,
+}
+
+impl ResourcePack {
+    pub fn create(manifest: ResourcePackManifest, source: Box<dyn ResourceSource>) -> Self {
+        Self { manifest, source }
+    }
+
+    /// Builds the manifest from the source content
+    pub fn from_source(slug: &str, priority: i32, source: Box<dyn ResourceSource>) -> Result<Self, String> {
+        let manifest = ResourcePackManifest::build(slug, priority, source.as_ref())?;
+        Ok(Self { manifest, source })
+    }
+
+    pub fn get_manifest(&self) -> &ResourcePackManifest {
+        &self.manifest
+    }
+}
+
+/// Asset which the client must have; `pack` is the slug of the pack it's taken from
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedAsset {
+    pack: String,
+    asset: ResourceAsset,
+}
+
+impl ResolvedAsset {
+    pub fn get_pack(&self) -> &String {
+        &self.pack
+    }
+
+    pub fn get_asset(&self) -> &ResourceAsset {
+        &self.asset
+    }
+}
+
+/// Difference between the client cache and the server resources
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ResourceDiff {
+    /// Missing or changed assets
+    pub download: Vec<ResourceUri>,
+    /// Assets which are not used by the server anymore
+    pub remove: Vec<ResourceUri>,
+}
+
+impl ResourceDiff {
+    pub fn is_empty(&self) -> bool {
+        self.download.is_empty() && self.remove.is_empty()
+    }
+}
+
+/// Resource packs layered by priority.
+///
+/// Asset is taken from the pack with the highest priority which contains it;
+/// with equal priority the pack added later wins.
+#[derive(Default)]
+pub struct ResourcePackStack {
+    packs: Vec<ResourcePack>,
+}
+
+impl ResourcePackStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, pack: ResourcePack) -> Result<(), String> {
+        let slug = pack.get_manifest().get_slug();
+        if self.packs.iter().any(|p| p.get_manifest().get_slug() == slug) {
+            return Err(format!("&cresource pack &4\"{}\" &cis already added", slug));
+        }
+        self.packs.push(pack);
+        // Stable sort keeps the adding order for equal priorities
+        self.packs.sort_by_key(|p| p.get_manifest().get_priority());
+        Ok(())
+    }
+
+    pub fn remove(&mut self, slug: &str) -> Option<ResourcePack> {
+        let index = self.packs.iter().position(|p| p.get_manifest().get_slug() == slug)?;
+        Some(self.packs.remove(index))
+    }
+
+    /// Packs from the lowest priority to the highest
+    pub fn iter_packs(&self) -> impl Iterator<Item = &ResourcePack> {
+        self.packs.iter()
+    }
+
+    /// Pack which provides the asset
+    pub fn resolve(&self, uri: &ResourceUri) -> Option<&ResourcePack> {
+        self.packs.iter().rev().find(|p| p.manifest.get_asset(uri).is_some())
+    }
+
+    /// Reads the asset from the resolved pack and checks its hash
+    pub fn read(&self, uri: &ResourceUri) -> Result<Vec<u8>, String> {
+        let Some(pack) = self.resolve(uri) else {
+            return Err(format!("&cresource &4\"{}\" &cnot found", uri));
+        };
+        let Some(data) = pack.source.read(uri)? else {
+            return Err(format!(
+                "&cresource &4\"{}\" &cis missing in pack &4\"{}\"",
+                uri,
+                pack.manifest.get_slug()
+            ));
+        };
+        if hash_resource(&data) != *pack.manifest.get_asset(uri).unwrap().get_hash() {
+            return Err(format!(
+                "&cresource &4\"{}\" &chash doesn't match in pack &4\"{}\"",
+                uri,
+                pack.manifest.get_slug()
+            ));
+        }
+        Ok(data)
+    }
+
+    /// Final set of the assets after layering
+    pub fn get_resolved_assets(&self) -> BTreeMap<ResourceUri, ResolvedAsset> {
+        let mut result = BTreeMap::new();
+        for pack in self.packs.iter() {
+            for (uri, asset) in pack.manifest.get_assets().iter() {
+                let resolved = ResolvedAsset {
+                    pack: pack.manifest.get_slug().clone(),
+                    asset: asset.clone(),
+                };
+                result.insert(uri.clone(), resolved);
+            }
+        }
+        result
+    }
+
+    /// Compares the hashes cached by the client with the resolved assets
+    pub fn diff(&self, client_hashes: &BTreeMap<ResourceUri, String>) -> ResourceDiff {
+        let resolved = self.get_resolved_assets();
+        let mut diff = ResourceDiff::default();
+        for (uri, resolved_asset) in resolved.iter() {
+            if client_hashes.get(uri) != Some(resolved_asset.asset.get_hash()) {
+                diff.download.push(uri.clone());
+            }
+        }
+        for uri in client_hashes.keys() {
+            if !resolved.contains_key(uri) {
+                diff.remove.push(uri.clone());
+            }
+        }
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{ResourcePack, ResourcePackStack};
+    use crate::resource_packs::{
+        manifest::{hash_resource, ResourcePackManifest},
+        source::{DirectorySource, MemorySource, ResourceSource},
+        uri::ResourceUri,
+    };
+
+    fn uri(s: &str) -> ResourceUri {
+        s.parse().unwrap()
+    }
+
+    fn memory_pack(slug: &str, priority: i32, assets: &[(&str, &[u8])]) -> ResourcePack {
+        let mut source = MemorySource::new();
+        for (path, data) in assets {
+            source.insert(uri(path), data.to_vec());
+        }
+        ResourcePack::from_source(slug, priority, Box::new(source)).unwrap()
+    }
+
+    #[test]
+    fn test_stack_layering() {
+        let mut stack = ResourcePackStack::new();
+        stack
+            .add(memory_pack("hd", 10, &[("default://block/dirt.png", b"hd dirt")]))
+            .unwrap();
+        stack
+            .add(memory_pack(
+                "base",
+                0,
+                &[
+                    ("default://block/dirt.png", b"dirt"),
+                    ("default://block/stone.png", b"stone"),
+                ],
+            ))
+            .unwrap();
+        assert!(stack.add(memory_pack("hd", 0, &[])).is_err());
+
+        assert_eq!(stack.read(&uri("default://block/dirt.png")).unwrap(), b"hd dirt");
+        assert_eq!(stack.read(&uri("default://block/stone.png")).unwrap(), b"stone");
+        assert!(stack.read(&uri("default://block/sand.png")).is_err());
+
+        let mut client = BTreeMap::new();
+        client.insert(uri("default://block/dirt.png"), hash_resource(b"dirt"));
+        client.insert(uri("default://block/stone.png"), hash_resource(b"stone"));
+        client.insert(uri("default://block/old.png"), hash_resource(b"old"));
+        let diff = stack.diff(&client);
+        assert_eq!(diff.download, vec![uri("default://block/dirt.png")]);
+        assert_eq!(diff.remove, vec![uri("default://block/old.png")]);
+
+        stack.remove("hd");
+        client.remove(&uri("default://block/old.png"));
+        assert!(stack.diff(&client).is_empty());
+    }
+
+    #[test]
+    fn test_directory_source() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("default/assets/block")).unwrap();
+        std::fs::write(tmp.path().join("default/assets/block/dirt.png"), b"dirt").unwrap();
+        std::fs::create_dir_all(tmp.path().join("gems")).unwrap();
+        std::fs::write(tmp.path().join("gems/ruby.png"), b"ruby").unwrap();
+        std::fs::create_dir_all(tmp.path().join(".git")).unwrap();
+        std::fs::write(tmp.path().join(".git/HEAD"), b"ref").unwrap();
+        std::fs::write(tmp.path().join("gems/.DS_Store"), b"").unwrap();
+
+        let source = DirectorySource::create(tmp.path());
+        assert_eq!(
+            source.list().unwrap(),
+            vec![uri("default://assets/block/dirt.png"), uri("gems://ruby.png")]
+        );
+
+        let manifest = ResourcePackManifest::build("local", 0, &source).unwrap();
+        let manifest = ResourcePackManifest::from_yaml(&manifest.to_yaml()).unwrap();
+        assert!(manifest.verify(&source).is_ok());
+
+        std::fs::write(tmp.path().join("gems/ruby.png"), b"changed").unwrap();
+        assert!(manifest.verify(&source).is_err());
+        assert!(source.read(&uri("gems://sapphire.png")).unwrap().is_none());
+    }
+
+    #[cfg(feature = "full")]
+    #[test]
+    fn test_zip_source() {
+        use crate::resource_packs::source::ZipSource;
+        use std::io::Write;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("pack.zip");
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+        writer
+            .start_file("default/block/dirt.png", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(b"zip dirt").unwrap();
+        writer
+            .start_file("__MACOSX/default/._dirt.png", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.finish().unwrap();
+
+        let source = ZipSource::open(&path).unwrap();
+        assert_eq!(source.list().unwrap(), vec![uri("default://block/dirt.png")]);
+        assert_eq!(
+            source.read(&uri("default://block/dirt.png")).unwrap().unwrap(),
+            b"zip dirt"
+        );
+        assert!(source.read(&uri("default://block/sand.png")).unwrap().is_none());
+    }
+}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+const REGEX_NAMESPACE: &str = r"^[a-z0-9_-]{1,32}$";
+
+/// Resource address "namespace://path/to/file.png".
+///
+/// Namespace is "default" for the built in resources or the slug of the plugin.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ResourceUri {
+    namespace: String,
+    path: String,
+}
+
+impl ResourceUri {
+    /// Path parts must be non-empty and can't start with "." (hidden files, "./" and "../").
+    pub fn create(namespace: impl Into<String>, path: impl Into<String>) -> Result<Self, String> {
+        let namespace = namespace.into();
+        let path = path.into();
+
+        if !Self::is_valid_namespace(&namespace) {
+            return Err(format!("&cresource namespace &4\"{}\" &cis invalid", namespace));
+        }
+        let path = path.trim_start_matches('/').to_string();
+        if path.is_empty() || path.contains('\\') || path.split('/').any(|p| p.is_empty() || p.starts_with('.')) {
+            return Err(format!("&cresource path &4\"{}\" &cis invalid", path));
+        }
+        Ok(Self { namespace, path })
+    }
+
+    pub fn is_valid_namespace(namespace: &str) -> bool {
+        let re = regex::Regex::new(REGEX_NAMESPACE).unwrap();
+        re.is_match(namespace)
+    }
+
+    pub fn get_namespace(&self) -> &String {
+        &self.namespace
+    }
+
+    pub fn get_path(&self) -> &String {
+        &self.path
+    }
+}
+
+impl FromStr for ResourceUri {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((namespace, path)) = s.split_once("://") else {
+            return Err(format!("&cresource &4\"{}\" &cmust be \"namespace://path\"", s));
+        };
+        Self::create(namespace, path)
+    }
+}
+
+impl Display for ResourceUri {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}://{}", self.namespace, self.path)
+    }
+}
+
+impl Serialize for ResourceUri {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ResourceUri {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ResourceUri;
+
+    #[test]
+    fn test_resource_uri() {
+        let uri: ResourceUri = "default://assets/block/dirt.png".parse().unwrap();
+        assert_eq!(uri.get_namespace(), "default");
+        assert_eq!(uri.get_path(), "assets/block/dirt.png");
+        assert_eq!(uri.to_string(), "default://assets/block/dirt.png");
+
+        assert!("assets/dirt.png".parse::<ResourceUri>().is_err());
+        assert!("Default://dirt.png".parse::<ResourceUri>().is_err());
+        assert!("default://../dirt.png".parse::<ResourceUri>().is_err());
+        assert!("default://a//dirt.png".parse::<ResourceUri>().is_err());
+        assert!("default://./dirt.png".parse::<ResourceUri>().is_err());
+        assert!("default://.git/config".parse::<ResourceUri>().is_err());
+    }
+}
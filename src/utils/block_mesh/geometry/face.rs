@@ -1,5 +1,7 @@
 use ilattice::glam::{IVec3, UVec3};
 
+use crate::blocks::block_type::CubeFace;
+
 use super::{Axis, AxisPermutation, SignedAxis, UnorientedQuad};
 
 /// Metadata that's used to aid in the geometric calculations for one of the 6 possible cube faces.
@@ -55,6 +57,13 @@ impl OrientedBlockFace {
         self.n.as_ivec3() * self.n_sign
     }
 
+    /// [`CubeFace`] with the same normal
+    pub fn get_cube_face(&self) -> CubeFace {
+        let axis = self.permutation.axes()[0].index();
+        let index = if self.n_sign > 0 { axis + 3 } else { axis };
+        CubeFace::from_index(index).unwrap()
+    }
+
     /// Returns the 4 corners of the quad in this order:
     ///
     /// ```text
@@ -118,6 +127,20 @@ impl OrientedBlockFace {
         quad_indices(start, self.n_sign * self.permutation.sign() > 0)
     }
 
+    /// Whether the texture U runs against the quad +U on this face.
+    ///
+    /// `u_flip_face` should correspond to the field on
+    /// [`QuadCoordinateConfig`](crate::QuadCoordinateConfig).
+    #[inline]
+    pub fn is_u_flipped(&self, u_flip_face: Axis) -> bool {
+        let face_normal_axis = self.permutation.axes()[0];
+        if self.n_sign < 0 {
+            u_flip_face != face_normal_axis
+        } else {
+            u_flip_face == face_normal_axis
+        }
+    }
+
     /// Returns the UV coordinates of the 4 corners of the quad. Returns
     /// vertices in the same order as [`OrientedBlockFace::quad_corners`].
     ///
@@ -135,12 +158,7 @@ impl OrientedBlockFace {
     /// coordinates from the `Quad`.
     #[inline]
     pub fn tex_coords(&self, u_flip_face: Axis, flip_v: bool, quad: &UnorientedQuad) -> [[f32; 2]; 4] {
-        let face_normal_axis = self.permutation.axes()[0];
-        let flip_u = if self.n_sign < 0 {
-            u_flip_face != face_normal_axis
-        } else {
-            u_flip_face == face_normal_axis
-        };
+        let flip_u = self.is_u_flipped(u_flip_face);
 
         match (flip_u, flip_v) {
             (false, false) => [
@@ -172,12 +190,7 @@ impl OrientedBlockFace {
 
     #[inline]
     pub fn tex_coords_godot(&self, u_flip_face: Axis, flip_v: bool, quad: &UnorientedQuad) -> [[f32; 2]; 4] {
-        let face_normal_axis = self.permutation.axes()[0];
-        let flip_u = if self.n_sign < 0 {
-            u_flip_face != face_normal_axis
-        } else {
-            u_flip_face == face_normal_axis
-        };
+        let flip_u = self.is_u_flipped(u_flip_face);
 
         let w = quad.width as f32;
         match (flip_u, flip_v) {
@@ -7,7 +7,7 @@ use ilattice::prelude::Extent;
 use ndcopy::fill3;
 use ndshape::Shape;
 
-use crate::blocks::voxel_visibility::{Voxel, VoxelVisibility};
+use crate::blocks::voxel_visibility::{is_face_visible, Voxel, VoxelVisibility};
 
 use super::{OrientedBlockFace, QuadBuffer, UnorientedQuad, bounds::assert_in_bounds};
 
@@ -156,6 +156,7 @@ fn greedy_quads_for_face<T, S, Merger>(
         } else {
             0u32.wrapping_sub(n_stride)
         },
+        face_index: face.get_cube_face().index(),
     };
 
     for _ in 0..num_slices {
@@ -167,15 +168,7 @@ fn greedy_quads_for_face<T, S, Merger>(
             let quad_min_array = quad_min.to_array();
             let quad_min_index = voxels_shape.linearize(quad_min_array);
             let quad_min_voxel = unsafe { voxels.get_unchecked(quad_min_index as usize) };
-            if unsafe {
-                !face_needs_mesh(
-                    quad_min_voxel,
-                    quad_min_index,
-                    face_strides.visibility_offset,
-                    voxels,
-                    visited,
-                )
-            } {
+            if unsafe { !face_needs_mesh(quad_min_voxel, quad_min_index, &face_strides, voxels, visited) } {
                 continue;
             }
             // We have at least one face that needs a mesh. We'll try to expand that face into the biggest quad we can find.
@@ -215,7 +208,7 @@ fn greedy_quads_for_face<T, S, Merger>(
 pub(crate) unsafe fn face_needs_mesh<T>(
     voxel: &T,
     voxel_stride: u32,
-    visibility_offset: u32,
+    face_strides: &FaceStrides,
     voxels: &[T],
     visited: &[bool],
 ) -> bool
@@ -226,21 +219,17 @@ where
         return false;
     }
 
-    let adjacent_voxel = voxels.get_unchecked(voxel_stride.wrapping_add(visibility_offset) as usize);
-
-    // TODO: If the face lies between two transparent voxels, we choose not to mesh it. We might need to extend the IsOpaque
-    // trait with different levels of transparency to support this.
-    match adjacent_voxel.get_visibility() {
-        VoxelVisibility::Empty => true,
-        VoxelVisibility::Translucent => voxel.get_visibility() == VoxelVisibility::Opaque,
-        VoxelVisibility::Opaque => false,
-        VoxelVisibility::NonVoxel => true,
-    }
+    let adjacent_voxel = voxels.get_unchecked(voxel_stride.wrapping_add(face_strides.visibility_offset) as usize);
+    is_face_visible(voxel, adjacent_voxel, face_strides.face_index)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{chunks::chunk_data::BlockDataInfo, utils::block_mesh::RIGHT_HANDED_Y_UP_CONFIG};
+    use crate::{
+        blocks::{block_shape::FULL_FACES, block_type::CubeFace},
+        chunks::chunk_data::BlockDataInfo,
+        utils::block_mesh::RIGHT_HANDED_Y_UP_CONFIG,
+    };
 
     use super::*;
     use ndshape::{ConstShape, ConstShape3u32};
@@ -275,6 +264,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_faces_order() {
+        type TestShape = ConstShape3u32<4, 4, 4>;
+        let mut samples = [ShapedVoxel(0); TestShape::SIZE as usize];
+        // Stone with a slab on top, which covers the top side of the stone
+        samples[TestShape {}.linearize([1, 1, 1]) as usize] = ShapedVoxel(FULL_FACES);
+        samples[TestShape {}.linearize([1, 2, 1]) as usize] = ShapedVoxel(1 << CubeFace::NegY.index());
+
+        let mut faces = RIGHT_HANDED_Y_UP_CONFIG.faces;
+        faces.reverse();
+        let mut buffer = GreedyQuadsBuffer::new(samples.len());
+        greedy_quads(&samples, &TestShape {}, [0; 3], [3; 3], &faces, &mut buffer);
+
+        for (face, group) in faces.iter().zip(buffer.quads.groups.iter()) {
+            let expected = match face.get_cube_face() {
+                CubeFace::PosY => 0,
+                _ => 1,
+            };
+            assert_eq!(group.len(), expected, "{:?}", face.get_cube_face());
+        }
+    }
+
     type SampleShape = ConstShape3u32<34, 34, 34>;
 
     /// Basic voxel type with one byte of texture layers
@@ -297,6 +308,40 @@ mod tests {
         }
     }
 
+    /// Voxel with the full faces mask, empty if 0
+    #[derive(Clone, Copy, Eq, PartialEq)]
+    struct ShapedVoxel(u8);
+
+    impl Voxel for ShapedVoxel {
+        fn get_visibility(&self) -> VoxelVisibility {
+            match self.0 {
+                0 => VoxelVisibility::Empty,
+                _ => VoxelVisibility::Opaque,
+            }
+        }
+
+        fn get_block_info(&self) -> &Option<BlockDataInfo> {
+            todo!()
+        }
+
+        fn get_full_faces(&self) -> u8 {
+            self.0
+        }
+    }
+
+    impl MergeVoxel for ShapedVoxel {
+        type MergeValue = Self;
+        type MergeValueFacingNeighbour = bool;
+
+        fn merge_value(&self) -> Self::MergeValue {
+            *self
+        }
+
+        fn merge_value_facing_neighbour(&self) -> Self::MergeValueFacingNeighbour {
+            true
+        }
+    }
+
     impl MergeVoxel for BoolVoxel {
         type MergeValue = Self;
         type MergeValueFacingNeighbour = bool;
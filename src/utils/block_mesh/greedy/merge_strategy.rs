@@ -42,6 +42,8 @@ pub struct FaceStrides {
     pub u_stride: u32,
     pub v_stride: u32,
     pub visibility_offset: u32,
+    /// [`CubeFace`](crate::blocks::block_type::CubeFace) index of the face
+    pub face_index: usize,
 }
 
 pub struct VoxelMerger<T> {
@@ -75,7 +77,7 @@ where
             visited,
             &quad_value,
             &quad_neighbour_value,
-            face_strides.visibility_offset,
+            face_strides,
             row_start_stride,
             face_strides.u_stride,
             max_width,
@@ -90,7 +92,7 @@ where
                 visited,
                 &quad_value,
                 &quad_neighbour_value,
-                face_strides.visibility_offset,
+                face_strides,
                 row_start_stride,
                 face_strides.u_stride,
                 quad_width,
@@ -112,7 +114,7 @@ impl<T> VoxelMerger<T> {
         visited: &[bool],
         quad_merge_voxel_value: &T::MergeValue,
         quad_merge_voxel_value_facing_neighbour: &T::MergeValueFacingNeighbour,
-        visibility_offset: u32,
+        face_strides: &FaceStrides,
         start_stride: u32,
         delta_stride: u32,
         max_width: u32,
@@ -124,9 +126,9 @@ impl<T> VoxelMerger<T> {
         let mut row_stride = start_stride;
         while quad_width < max_width {
             let voxel = voxels.get_unchecked(row_stride as usize);
-            let neighbour = voxels.get_unchecked(row_stride.wrapping_add(visibility_offset) as usize);
+            let neighbour = voxels.get_unchecked(row_stride.wrapping_add(face_strides.visibility_offset) as usize);
 
-            if !face_needs_mesh(voxel, row_stride, visibility_offset, voxels, visited) {
+            if !face_needs_mesh(voxel, row_stride, face_strides, voxels, visited) {
                 break;
             }
 
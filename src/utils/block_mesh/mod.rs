@@ -2,9 +2,11 @@ pub mod bounds;
 pub mod buffer;
 pub mod geometry;
 pub mod greedy;
+pub mod shaped;
 pub mod simple;
 
 pub use buffer::*;
 #[doc(inline)]
 pub use geometry::*;
+pub use shaped::*;
 pub use simple::*;
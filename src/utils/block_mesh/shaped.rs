@@ -0,0 +1,224 @@
+use crate::blocks::{
+    block_shape::ShapeBox,
+    block_type::CubeFace,
+    voxel_visibility::{Voxel, VoxelVisibility},
+};
+
+use super::{Axis, OrientedBlockFace};
+
+/// Side of the shape box in the block local coordinates
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShapeQuad {
+    pub face: CubeFace,
+    /// Corners in the order of [`OrientedBlockFace::quad_corners`]
+    pub positions: [[f32; 3]; 4],
+    /// Texture U and V of the corners from 0 to 1, so a slab side shows the half of the texture.
+    ///
+    /// Flipped the same way as [`OrientedBlockFace::tex_coords`] of the cube faces.
+    pub tex_coords: [[f32; 2]; 4],
+}
+
+/// Quads of the partial block shape, the boxes are taken from
+/// [`BlockShape::get_rotated_boxes`](crate::blocks::block_shape::BlockShape::get_rotated_boxes).
+///
+/// Box sides on the block boundary are culled when the opaque neighbour covers them,
+/// sides touching another box of the same shape are culled too.
+/// `neighbours` are in the [`CubeFace`] order; `u_flip_face` and `flip_v`
+/// are the same as for [`OrientedBlockFace::tex_coords`].
+pub fn shape_block_quads<N: Voxel>(
+    boxes: &[ShapeBox],
+    faces: &[OrientedBlockFace; 6],
+    u_flip_face: Axis,
+    flip_v: bool,
+    neighbours: [&N; 6],
+) -> Vec<ShapeQuad> {
+    let mut quads = Vec::new();
+    for (box_index, shape_box) in boxes.iter().enumerate() {
+        for face in CubeFace::ALL {
+            let depth = shape_box.get_face_depth(face);
+            let (min, max) = shape_box.get_face_rect(face);
+
+            let boundary = match face.index() < 3 {
+                true => 0.0,
+                false => 1.0,
+            };
+            if depth == boundary {
+                let neighbour = neighbours[face.index()];
+                let opposite = (face.index() + 3) % 6;
+                if neighbour.get_visibility() == VoxelVisibility::Opaque
+                    && neighbour.get_full_faces() & (1 << opposite) != 0
+                {
+                    continue;
+                }
+            }
+
+            let opposite_face = CubeFace::from_index((face.index() + 3) % 6).unwrap();
+            let hidden = boxes.iter().enumerate().any(|(i, other)| {
+                let (other_min, other_max) = other.get_face_rect(opposite_face);
+                i != box_index
+                    && other.get_face_depth(opposite_face) == depth
+                    && other_min[0] <= min[0]
+                    && other_min[1] <= min[1]
+                    && max[0] <= other_max[0]
+                    && max[1] <= other_max[1]
+            });
+            if hidden {
+                continue;
+            }
+
+            let Some(oriented) = faces.iter().find(|f| f.get_cube_face() == face) else {
+                continue;
+            };
+            let flip_u = oriented.is_u_flipped(u_flip_face);
+            quads.push(face_quad(shape_box, face, oriented, flip_u, flip_v));
+        }
+    }
+    quads
+}
+
+fn face_quad(
+    shape_box: &ShapeBox,
+    face: CubeFace,
+    oriented: &OrientedBlockFace,
+    flip_u: bool,
+    flip_v: bool,
+) -> ShapeQuad {
+    let [n_axis, u_axis, v_axis] = oriented.permutation().axes();
+    let (i_n, i_u, i_v) = (n_axis.index(), u_axis.index(), v_axis.index());
+    let depth = shape_box.get_face_depth(face);
+
+    let corner = |u: f32, v: f32| {
+        let mut p = [0.0; 3];
+        p[i_n] = depth;
+        p[i_u] = u;
+        p[i_v] = v;
+        p
+    };
+    let (min_u, max_u) = (shape_box.min[i_u], shape_box.max[i_u]);
+    let (min_v, max_v) = (shape_box.min[i_v], shape_box.max[i_v]);
+    let tex = |u: f32, v: f32| [if flip_u { 1.0 - u } else { u }, if flip_v { 1.0 - v } else { v }];
+    ShapeQuad {
+        face,
+        positions: [
+            corner(min_u, min_v),
+            corner(max_u, min_v),
+            corner(min_u, max_v),
+            corner(max_u, max_v),
+        ],
+        tex_coords: [
+            tex(min_u, min_v),
+            tex(max_u, min_v),
+            tex(min_u, max_v),
+            tex(max_u, max_v),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::shape_block_quads;
+    use crate::{
+        blocks::{
+            block_shape::{BlockShape, FULL_FACES},
+            block_type::CubeFace,
+            chunk_shape_info::ChunkShapeInfo,
+            voxel_visibility::{Voxel, VoxelVisibility},
+        },
+        utils::block_mesh::{UnorientedQuad, RIGHT_HANDED_Y_UP_CONFIG},
+    };
+
+    #[test]
+    fn test_shape_quads_culling() {
+        let air = ChunkShapeInfo::create(VoxelVisibility::Empty, None);
+        let stone = ChunkShapeInfo::create(VoxelVisibility::Opaque, None);
+        let slab =
+            ChunkShapeInfo::create(VoxelVisibility::Opaque, None).full_faces(BlockShape::Slab.get_full_faces(&None));
+        assert_ne!(slab.get_full_faces(), FULL_FACES);
+
+        // Stone at -X and a slab below
+        let neighbours = [&stone, &slab, &air, &air, &air, &air];
+        let quads = shape_block_quads(
+            &BlockShape::Slab.get_boxes(),
+            &RIGHT_HANDED_Y_UP_CONFIG.faces,
+            RIGHT_HANDED_Y_UP_CONFIG.u_flip_face,
+            true,
+            neighbours,
+        );
+        let faces: Vec<CubeFace> = quads.iter().map(|q| q.face).collect();
+        // Slab below doesn't cover its top side
+        assert_eq!(
+            faces,
+            vec![
+                CubeFace::NegY,
+                CubeFace::NegZ,
+                CubeFace::PosX,
+                CubeFace::PosY,
+                CubeFace::PosZ
+            ]
+        );
+        let top = quads.iter().find(|q| q.face == CubeFace::PosY).unwrap();
+        assert!(top.positions.iter().all(|p| p[1] == 0.5));
+
+        // Touching sides of the stairs boxes are not meshed
+        let quads = shape_block_quads(
+            &BlockShape::Stairs.get_boxes(),
+            &RIGHT_HANDED_Y_UP_CONFIG.faces,
+            RIGHT_HANDED_Y_UP_CONFIG.u_flip_face,
+            true,
+            [&air; 6],
+        );
+        assert_eq!(quads.len(), 11);
+    }
+
+    #[test]
+    fn test_shape_quads_tex_coords() {
+        let air = ChunkShapeInfo::create(VoxelVisibility::Empty, None);
+        let config = &RIGHT_HANDED_Y_UP_CONFIG;
+        let unit = UnorientedQuad {
+            minimum: [0, 0, 0],
+            width: 1,
+            height: 1,
+        };
+
+        // Full box sides match the cube faces
+        let quads = shape_block_quads(
+            &BlockShape::Cube.get_boxes(),
+            &config.faces,
+            config.u_flip_face,
+            true,
+            [&air; 6],
+        );
+        for quad in quads.iter() {
+            let face = &config.faces[quad.face.index()];
+            assert_eq!(quad.tex_coords, face.tex_coords(config.u_flip_face, true, &unit));
+        }
+
+        // Slab side shows the lower half of the texture, v of 0.0 is the top
+        let quads = shape_block_quads(
+            &BlockShape::Slab.get_boxes(),
+            &config.faces,
+            config.u_flip_face,
+            true,
+            [&air; 6],
+        );
+        let side = quads.iter().find(|q| q.face == CubeFace::PosZ).unwrap();
+        assert!(side.tex_coords.iter().all(|[_, v]| *v >= 0.5));
+    }
+
+    #[test]
+    fn test_shape_quads_face_order() {
+        let air = ChunkShapeInfo::create(VoxelVisibility::Empty, None);
+        let mut faces = RIGHT_HANDED_Y_UP_CONFIG.faces;
+        faces.reverse();
+        let quads = |faces| {
+            shape_block_quads(
+                &BlockShape::Slab.get_boxes(),
+                faces,
+                RIGHT_HANDED_Y_UP_CONFIG.u_flip_face,
+                true,
+                [&air; 6],
+            )
+        };
+        assert_eq!(quads(&faces), quads(&RIGHT_HANDED_Y_UP_CONFIG.faces));
+    }
+}
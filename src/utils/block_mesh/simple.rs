@@ -1,4 +1,4 @@
-use crate::blocks::voxel_visibility::{IdentityVoxel, Voxel, VoxelVisibility};
+use crate::blocks::voxel_visibility::{is_face_visible, IdentityVoxel, Voxel, VoxelVisibility};
 
 use super::{OrientedBlockFace, UnitQuadBuffer, UnorientedUnitQuad, bounds::assert_in_bounds};
 use ilattice::glam::UVec3;
@@ -46,6 +46,7 @@ pub fn visible_block_faces_with_voxel_view<'a, T, V, S>(
     let interior = Extent::from_min_and_shape(interior.minimum.as_uvec3(), interior.shape.as_uvec3());
 
     let kernel_strides = faces.map(|face| voxels_shape.linearize(face.signed_normal().as_uvec3().to_array()));
+    let cube_faces = faces.map(|face| face.get_cube_face().index());
 
     for p in interior.iter3() {
         let p_array = p.to_array();
@@ -60,16 +61,7 @@ pub fn visible_block_faces_with_voxel_view<'a, T, V, S>(
             let neighbor_index = p_index.wrapping_add(face_stride);
             let neighbor_voxel = V::from(unsafe { voxels.get_unchecked(neighbor_index as usize) });
 
-            // TODO: If the face lies between two transparent voxels, we choose not to mesh it. We might need to extend the
-            // IsOpaque trait with different levels of transparency to support this.
-            let face_needs_mesh = match neighbor_voxel.get_visibility() {
-                VoxelVisibility::Empty => true,
-                VoxelVisibility::Translucent => p_voxel.get_visibility() == VoxelVisibility::Opaque,
-                VoxelVisibility::Opaque => false,
-                VoxelVisibility::NonVoxel => true,
-            };
-
-            if face_needs_mesh {
+            if is_face_visible(&p_voxel, &neighbor_voxel, cube_faces[face_index]) {
                 output.groups[face_index].push(UnorientedUnitQuad {
                     minimum: p_array,
                     block_info: p_voxel.get_block_info().clone(),
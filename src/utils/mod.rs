@@ -13,7 +13,7 @@ pub mod spiral_iterator;
 
 use std::hash::{DefaultHasher, Hash, Hasher};
 
-use crate::CHUNK_SIZE;
+use crate::{resource_packs::uri::ResourceUri, CHUNK_SIZE};
 
 /// https://github.com/feather-rs/feather
 /// feather/utils/src/lib.rs
@@ -40,13 +40,10 @@ pub fn calculate_hash<T: Hash>(obj: T) -> u64 {
 }
 
 // Split "test://test/file.glb" into ("test", "test/file.glb")
+// None if the path is not a valid ResourceUri
 pub fn split_resource_path(path: &String) -> Option<(String, String)> {
-    let s: Vec<&str> = path.split("://").collect();
-    if s.len() < 2 {
-        return None;
-    }
-    let res_path = s[1..s.len()].join("/");
-    return Some((s.get(0).unwrap().to_string(), res_path));
+    let uri: ResourceUri = path.parse().ok()?;
+    Some((uri.get_namespace().clone(), uri.get_path().clone()))
 }
 
 pub fn validate_username(username: &String) -> bool {
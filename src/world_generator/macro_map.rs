@@ -0,0 +1,651 @@
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+use std::collections::HashMap;
+
+use crate::chunks::{
+    block_position::{BlockPosition, BlockPositionTrait},
+    chunk_data::WorldMacroData,
+    chunk_position::ChunkPosition,
+};
+
+use super::random::ChunkRandom;
+
+pub const MACRO_MAP_FORMAT_VERSION: u16 = 1;
+
+/// Largest `width * depth` of the macro map; 1024x1024 cells of 64 blocks cover 64k blocks
+pub const MAX_MACRO_MAP_CELLS: usize = 1024 * 1024;
+
+/// Largest count of the rivers, regions and points of interest of every kind
+pub const MAX_MACRO_MAP_COUNT: u32 = 4096;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PointOfInterestSettings {
+    pub kind: String,
+    pub count: u32,
+}
+
+/// Settings of the macro map; every field is optional in yaml.
+#[serde_inline_default]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MacroMapSettings {
+    /// Map size in cells
+    #[serde_inline_default(128)]
+    width: u32,
+    #[serde_inline_default(128)]
+    depth: u32,
+
+    /// Size of a single cell in blocks
+    #[serde_inline_default(64)]
+    cell_size: u32,
+
+    /// Size of the continents noise in cells
+    #[serde_inline_default(24.0)]
+    continent_scale: f32,
+
+    /// Raises the elevation everywhere; higher values give more land
+    #[serde_inline_default(0.15)]
+    land_bias: f32,
+
+    /// Lowers the elevation near the map edges so the world is surrounded by ocean
+    #[serde_inline_default(1.0)]
+    edge_falloff: f32,
+
+    #[serde_inline_default(24)]
+    river_count: u32,
+
+    /// Minimal elevation of a river source
+    #[serde_inline_default(0.25)]
+    river_source_elevation: f32,
+
+    #[serde_inline_default(48)]
+    region_count: u32,
+
+    #[serde_inline_default(vec!["plains".to_string(), "forest".to_string(), "desert".to_string(), "taiga".to_string()])]
+    biomes: Vec<String>,
+
+    #[serde(default)]
+    points_of_interest: Vec<PointOfInterestSettings>,
+}
+
+impl Default for MacroMapSettings {
+    fn default() -> Self {
+        serde_yaml::from_value(serde_yaml::Value::Mapping(Default::default())).unwrap()
+    }
+}
+
+impl MacroMapSettings {
+    /// Reads settings from the world generator settings; `None` gives the defaults.
+    pub fn from_settings(settings: &Option<serde_yaml::Value>) -> Result<Self, String> {
+        let settings = match settings {
+            Some(s) => serde_yaml::from_value(s.clone()).map_err(|e| format!("Macro map settings error: {}", e))?,
+            None => Self::default(),
+        };
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.width == 0 || self.depth == 0 || self.cell_size == 0 {
+            return Err("Macro map size and cell_size must be greater than zero".to_string());
+        }
+        if get_cells_count(self.width, self.depth).is_none_or(|c| c > MAX_MACRO_MAP_CELLS) {
+            return Err(format!(
+                "Macro map size {}x{} is too large; max {} cells",
+                self.width, self.depth, MAX_MACRO_MAP_CELLS
+            ));
+        }
+        if !self.continent_scale.is_finite() || self.continent_scale <= 0.0 {
+            return Err(format!(
+                "Macro map continent_scale must be greater than zero; got {}",
+                self.continent_scale
+            ));
+        }
+        for (name, value) in [
+            ("land_bias", self.land_bias),
+            ("edge_falloff", self.edge_falloff),
+            ("river_source_elevation", self.river_source_elevation),
+        ] {
+            if !value.is_finite() {
+                return Err(format!("Macro map {} must be a finite number; got {}", name, value));
+            }
+        }
+        if self.region_count == 0 || self.biomes.is_empty() {
+            return Err("Macro map requires at least one region and one biome".to_string());
+        }
+        let mut counts = vec![
+            ("region_count".to_string(), self.region_count),
+            ("river_count".to_string(), self.river_count),
+        ];
+        for poi in self.points_of_interest.iter() {
+            counts.push((format!("\"{}\" count", poi.kind), poi.count));
+        }
+        for (name, count) in counts {
+            if count > MAX_MACRO_MAP_COUNT {
+                return Err(format!(
+                    "Macro map {} must not be greater than {}; got {}",
+                    name, MAX_MACRO_MAP_COUNT, count
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn size(mut self, width: u32, depth: u32) -> Self {
+        self.width = width;
+        self.depth = depth;
+        self
+    }
+
+    pub fn cell_size(mut self, cell_size: u32) -> Self {
+        self.cell_size = cell_size;
+        self
+    }
+
+    pub fn point_of_interest(mut self, kind: impl Into<String>, count: u32) -> Self {
+        self.points_of_interest.push(PointOfInterestSettings {
+            kind: kind.into(),
+            count,
+        });
+        self
+    }
+}
+
+/// Node of the river graph, placed at the center of the macro map cell.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RiverNode {
+    pub x: i64,
+    pub z: i64,
+
+    /// Number of river sources upstream of this node
+    pub flow: u32,
+
+    /// Index of the next node down the river; `None` for the mouth
+    pub downstream: Option<usize>,
+}
+
+/// Center of the region; every block belongs to the nearest region seed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RegionSeed {
+    pub x: i64,
+    pub z: i64,
+    pub biome: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PointOfInterest {
+    pub kind: String,
+    pub x: i64,
+    pub z: i64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RiverSample {
+    /// Distance in blocks to the nearest river segment
+    pub distance: f32,
+    pub flow: u32,
+}
+
+/// Low resolution layout of the whole world.
+///
+/// Generated once per world and stored in [`WorldMacroData`], so chunk
+/// generators can sample large scale geography at any block
+/// instead of relying on local noise only.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MacroMap {
+    version: u16,
+    width: u32,
+    depth: u32,
+    cell_size: u32,
+
+    /// Block position of the minimum corner of the map
+    origin_x: i64,
+    origin_z: i64,
+
+    /// Elevation of every cell; above zero is land
+    elevation: Vec<f32>,
+    rivers: Vec<RiverNode>,
+    regions: Vec<RegionSeed>,
+    points_of_interest: Vec<PointOfInterest>,
+}
+
+impl MacroMap {
+    pub fn generate(seed: u64, settings: &MacroMapSettings) -> Result<Self, String> {
+        settings.validate()?;
+        let (width, depth, cell_size) = (settings.width, settings.depth, settings.cell_size);
+        let mut map = Self {
+            version: MACRO_MAP_FORMAT_VERSION,
+            width,
+            depth,
+            cell_size,
+            origin_x: -((width / 2) as i64) * cell_size as i64,
+            origin_z: -((depth / 2) as i64) * cell_size as i64,
+            elevation: Vec::with_capacity(get_cells_count(width, depth).unwrap_or_default()),
+            rivers: Default::default(),
+            regions: Default::default(),
+            points_of_interest: Default::default(),
+        };
+
+        for z in 0..depth {
+            for x in 0..width {
+                let noise = fractal_noise(
+                    seed,
+                    x as f32 / settings.continent_scale,
+                    z as f32 / settings.continent_scale,
+                );
+
+                // Distance to the map center, from 0.0 on the middle half of the map to 1.0 at the edges
+                let dx = (x as f32 + 0.5) / width as f32 * 2.0 - 1.0;
+                let dz = (z as f32 + 0.5) / depth as f32 * 2.0 - 1.0;
+                let edge = ((dx.abs().max(dz.abs()) - 0.5) * 2.0).max(0.0);
+
+                map.elevation
+                    .push(noise + settings.land_bias - settings.edge_falloff * edge * edge);
+            }
+        }
+
+        map.generate_rivers(seed, settings);
+        map.generate_regions(seed, settings);
+        map.generate_points_of_interest(seed, settings);
+        Ok(map)
+    }
+
+    fn generate_rivers(&mut self, seed: u64, settings: &MacroMapSettings) {
+        let mut rng = ChunkRandom::create(seed, &ChunkPosition::zero(), "macro_rivers");
+        let mut nodes_by_cell: HashMap<usize, usize> = HashMap::new();
+
+        let mut sources = 0;
+        for _ in 0..settings.river_count.saturating_mul(10) {
+            if sources >= settings.river_count {
+                break;
+            }
+            let source = rng.range(0, self.elevation.len() as i64) as usize;
+            if self.elevation[source] < settings.river_source_elevation || nodes_by_cell.contains_key(&source) {
+                continue;
+            }
+            sources += 1;
+
+            // Follow the steepest descent until the ocean, a local minimum or another river
+            let mut cell = source;
+            let mut previous: Option<usize> = None;
+            loop {
+                if let Some(existing) = nodes_by_cell.get(&cell) {
+                    let mut next = Some(*existing);
+                    if let Some(previous) = previous {
+                        self.rivers[previous].downstream = next;
+                    }
+                    while let Some(index) = next {
+                        self.rivers[index].flow += 1;
+                        next = self.rivers[index].downstream;
+                    }
+                    break;
+                }
+
+                let (x, z) = self.get_cell_center(cell);
+                let index = self.rivers.len();
+                self.rivers.push(RiverNode {
+                    x,
+                    z,
+                    flow: 1,
+                    downstream: None,
+                });
+                nodes_by_cell.insert(cell, index);
+                if let Some(previous) = previous {
+                    self.rivers[previous].downstream = Some(index);
+                }
+                previous = Some(index);
+
+                if self.elevation[cell] <= 0.0 {
+                    break;
+                }
+                match self.get_lowest_neighbour(cell) {
+                    Some(next) => cell = next,
+                    None => break,
+                }
+            }
+        }
+    }
+
+    fn generate_regions(&mut self, seed: u64, settings: &MacroMapSettings) {
+        let mut rng = ChunkRandom::create(seed, &ChunkPosition::zero(), "macro_regions");
+        let (size_x, size_z) = self.get_size_in_blocks();
+        for _ in 0..settings.region_count {
+            let Some(biome) = rng.choose(&settings.biomes) else {
+                break;
+            };
+            self.regions.push(RegionSeed {
+                x: self.origin_x + rng.range(0, size_x),
+                z: self.origin_z + rng.range(0, size_z),
+                biome: biome.clone(),
+            });
+        }
+    }
+
+    fn generate_points_of_interest(&mut self, seed: u64, settings: &MacroMapSettings) {
+        for poi in settings.points_of_interest.iter() {
+            let mut rng = ChunkRandom::create(seed, &ChunkPosition::zero(), &format!("macro_poi_{}", poi.kind));
+            let mut placed = 0;
+            for _ in 0..poi.count.saturating_mul(10) {
+                if placed >= poi.count {
+                    break;
+                }
+                let cell = rng.range(0, self.elevation.len() as i64) as usize;
+                if self.elevation[cell] <= 0.0 {
+                    continue;
+                }
+                let (x, z) = self.get_cell_center(cell);
+                self.points_of_interest.push(PointOfInterest {
+                    kind: poi.kind.clone(),
+                    x,
+                    z,
+                });
+                placed += 1;
+            }
+        }
+    }
+
+    fn get_cell_center(&self, cell: usize) -> (i64, i64) {
+        let x = (cell as u32 % self.width) as i64;
+        let z = (cell as u32 / self.width) as i64;
+        let half = self.cell_size as i64 / 2;
+        (
+            self.origin_x + x * self.cell_size as i64 + half,
+            self.origin_z + z * self.cell_size as i64 + half,
+        )
+    }
+
+    fn get_lowest_neighbour(&self, cell: usize) -> Option<usize> {
+        let x = (cell as u32 % self.width) as i64;
+        let z = (cell as u32 / self.width) as i64;
+        let mut lowest: Option<usize> = None;
+        let mut lowest_elevation = self.elevation[cell];
+        for dz in -1..=1 {
+            for dx in -1..=1 {
+                let (nx, nz) = (x + dx, z + dz);
+                if (dx == 0 && dz == 0) || nx < 0 || nz < 0 || nx >= self.width as i64 || nz >= self.depth as i64 {
+                    continue;
+                }
+                let neighbour = (nz * self.width as i64 + nx) as usize;
+                if self.elevation[neighbour] < lowest_elevation {
+                    lowest_elevation = self.elevation[neighbour];
+                    lowest = Some(neighbour);
+                }
+            }
+        }
+        lowest
+    }
+
+    fn get_cell_elevation(&self, x: i64, z: i64) -> f32 {
+        let x = x.clamp(0, self.width as i64 - 1);
+        let z = z.clamp(0, self.depth as i64 - 1);
+        self.elevation[(z * self.width as i64 + x) as usize]
+    }
+
+    /// Map size in blocks by x and z
+    pub fn get_size_in_blocks(&self) -> (i64, i64) {
+        (
+            self.width as i64 * self.cell_size as i64,
+            self.depth as i64 * self.cell_size as i64,
+        )
+    }
+
+    pub fn get_cell_size(&self) -> u32 {
+        self.cell_size
+    }
+
+    /// Elevation interpolated between cell centers; outside of the map the edge value is used.
+    pub fn get_elevation(&self, position: &BlockPosition) -> f32 {
+        let cell_size = self.cell_size as f32;
+        let fx = (position.get_x() - self.origin_x) as f32 / cell_size - 0.5;
+        let fz = (position.get_z() - self.origin_z) as f32 / cell_size - 0.5;
+        let (x, z) = (fx.floor() as i64, fz.floor() as i64);
+        let (tx, tz) = (fx - x as f32, fz - z as f32);
+
+        let top = lerp(self.get_cell_elevation(x, z), self.get_cell_elevation(x + 1, z), tx);
+        let bottom = lerp(
+            self.get_cell_elevation(x, z + 1),
+            self.get_cell_elevation(x + 1, z + 1),
+            tx,
+        );
+        lerp(top, bottom, tz)
+    }
+
+    pub fn is_land(&self, position: &BlockPosition) -> bool {
+        self.get_elevation(position) > 0.0
+    }
+
+    /// The region of the nearest region seed
+    pub fn get_region(&self, position: &BlockPosition) -> Option<&RegionSeed> {
+        self.regions.iter().min_by_key(|r| {
+            let (dx, dz) = (r.x - position.get_x(), r.z - position.get_z());
+            dx * dx + dz * dz
+        })
+    }
+
+    pub fn get_biome(&self, position: &BlockPosition) -> Option<&String> {
+        self.get_region(position).map(|r| &r.biome)
+    }
+
+    /// The nearest river segment; `None` if the map has no rivers.
+    pub fn get_nearest_river(&self, position: &BlockPosition) -> Option<RiverSample> {
+        let (px, pz) = (position.get_x() as f32, position.get_z() as f32);
+        let mut nearest: Option<RiverSample> = None;
+        for node in self.rivers.iter() {
+            let (ax, az) = (node.x as f32, node.z as f32);
+            let distance = match node.downstream {
+                Some(downstream) => {
+                    let next = &self.rivers[downstream];
+                    distance_to_segment(px, pz, ax, az, next.x as f32, next.z as f32)
+                }
+                None => ((px - ax).powi(2) + (pz - az).powi(2)).sqrt(),
+            };
+            if nearest.is_none_or(|n| distance < n.distance) {
+                nearest = Some(RiverSample {
+                    distance,
+                    flow: node.flow,
+                });
+            }
+        }
+        nearest
+    }
+
+    pub fn get_rivers(&self) -> &Vec<RiverNode> {
+        &self.rivers
+    }
+
+    pub fn get_regions(&self) -> &Vec<RegionSeed> {
+        &self.regions
+    }
+
+    pub fn get_points_of_interest(&self) -> &Vec<PointOfInterest> {
+        &self.points_of_interest
+    }
+
+    /// Points of interest located inside the chunk
+    pub fn get_chunk_points_of_interest<'a>(
+        &'a self,
+        chunk_position: &'a ChunkPosition,
+    ) -> impl Iterator<Item = &'a PointOfInterest> {
+        self.points_of_interest
+            .iter()
+            .filter(move |p| BlockPosition::new(p.x, 0, p.z).get_chunk_position() == *chunk_position)
+    }
+
+    pub fn to_macro_data(&self) -> WorldMacroData {
+        WorldMacroData::create(serde_yaml::to_value(self).unwrap())
+    }
+
+    pub fn from_macro_data(data: &WorldMacroData) -> Result<Self, String> {
+        let map: Self =
+            serde_yaml::from_value(data.get_data().clone()).map_err(|e| format!("Macro map decode error: {}", e))?;
+        if map.version != MACRO_MAP_FORMAT_VERSION {
+            return Err(format!(
+                "Macro map version {} is not supported (expected {})",
+                map.version, MACRO_MAP_FORMAT_VERSION
+            ));
+        }
+        if get_cells_count(map.width, map.depth) != Some(map.elevation.len()) || map.cell_size == 0 {
+            return Err(format!(
+                "Macro map size {}x{} doesn't match the elevation data",
+                map.width, map.depth
+            ));
+        }
+        if map
+            .rivers
+            .iter()
+            .any(|r| r.downstream.is_some_and(|d| d >= map.rivers.len()))
+        {
+            return Err("Macro map river graph is broken".to_string());
+        }
+        Ok(map)
+    }
+}
+
+fn get_cells_count(width: u32, depth: u32) -> Option<usize> {
+    (width as usize).checked_mul(depth as usize)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+fn distance_to_segment(px: f32, pz: f32, ax: f32, az: f32, bx: f32, bz: f32) -> f32 {
+    let (dx, dz) = (bx - ax, bz - az);
+    let length = dx * dx + dz * dz;
+    let t = match length > 0.0 {
+        true => (((px - ax) * dx + (pz - az) * dz) / length).clamp(0.0, 1.0),
+        false => 0.0,
+    };
+    ((px - ax - t * dx).powi(2) + (pz - az - t * dz).powi(2)).sqrt()
+}
+
+/// Random value in `[-1.0, 1.0)` at the lattice point
+fn lattice_value(seed: u64, x: i64, z: i64, octave: u32) -> f32 {
+    let mut rng = ChunkRandom::create(
+        seed.wrapping_add(octave as u64),
+        &ChunkPosition::new(x, z),
+        "macro_noise",
+    );
+    rng.next_f32() * 2.0 - 1.0
+}
+
+/// Smoothed value noise, uses integer hashing only so the result is the same on every platform.
+fn value_noise(seed: u64, x: f32, z: f32, octave: u32) -> f32 {
+    let (x0, z0) = (x.floor() as i64, z.floor() as i64);
+    let (tx, tz) = (x - x0 as f32, z - z0 as f32);
+    let (sx, sz) = (tx * tx * (3.0 - 2.0 * tx), tz * tz * (3.0 - 2.0 * tz));
+
+    let top = lerp(
+        lattice_value(seed, x0, z0, octave),
+        lattice_value(seed, x0 + 1, z0, octave),
+        sx,
+    );
+    let bottom = lerp(
+        lattice_value(seed, x0, z0 + 1, octave),
+        lattice_value(seed, x0 + 1, z0 + 1, octave),
+        sx,
+    );
+    lerp(top, bottom, sz)
+}
+
+fn fractal_noise(seed: u64, x: f32, z: f32) -> f32 {
+    let mut value = 0.0;
+    let (mut amplitude, mut frequency) = (0.5, 1.0);
+    for octave in 0..4 {
+        value += value_noise(seed, x * frequency, z * frequency, octave) * amplitude;
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MacroMap, MacroMapSettings};
+    use crate::chunks::{block_position::BlockPosition, chunk_data::WorldMacroData};
+
+    fn settings() -> MacroMapSettings {
+        MacroMapSettings::default()
+            .size(48, 48)
+            .cell_size(32)
+            .point_of_interest("village", 5)
+    }
+
+    #[test]
+    fn test_macro_map_is_deterministic() {
+        let a = MacroMap::generate(7, &settings()).unwrap();
+        let b = MacroMap::generate(7, &settings()).unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a, MacroMap::generate(8, &settings()).unwrap());
+    }
+
+    #[test]
+    fn test_macro_map_layout() {
+        let map = MacroMap::generate(7, &settings()).unwrap();
+
+        // Map edges are always ocean
+        assert!(!map.is_land(&BlockPosition::new(-760, 0, -760)));
+        assert!(!map.is_land(&BlockPosition::new(100_000, 0, 0)));
+
+        assert!(map.get_biome(&BlockPosition::new(0, 0, 0)).is_some());
+        assert_eq!(map.get_points_of_interest().len(), 5);
+        for poi in map.get_points_of_interest() {
+            assert!(map.is_land(&BlockPosition::new(poi.x, 0, poi.z)));
+        }
+    }
+
+    #[test]
+    fn test_macro_map_rivers_flow_down() {
+        let map = MacroMap::generate(7, &settings()).unwrap();
+        assert!(!map.get_rivers().is_empty());
+        for node in map.get_rivers() {
+            let Some(downstream) = node.downstream else {
+                continue;
+            };
+            let next = &map.get_rivers()[downstream];
+            let elevation = map.get_elevation(&BlockPosition::new(node.x, 0, node.z));
+            let next_elevation = map.get_elevation(&BlockPosition::new(next.x, 0, next.z));
+            assert!(next_elevation < elevation);
+            assert!(next.flow >= node.flow);
+        }
+
+        let node = &map.get_rivers()[0];
+        let sample = map.get_nearest_river(&BlockPosition::new(node.x, 0, node.z)).unwrap();
+        assert_eq!(sample.distance, 0.0);
+    }
+
+    #[test]
+    fn test_macro_map_macro_data() {
+        let map = MacroMap::generate(3, &settings()).unwrap();
+        let data = map.to_macro_data();
+        assert_eq!(MacroMap::from_macro_data(&data).unwrap(), map);
+
+        assert!(MacroMap::from_macro_data(&WorldMacroData::default()).is_err());
+    }
+
+    #[test]
+    fn test_macro_map_settings() {
+        let yaml: serde_yaml::Value = serde_yaml::from_str("width: 10\nbiomes: [swamp]").unwrap();
+        let settings = MacroMapSettings::from_settings(&Some(yaml)).unwrap();
+        assert_eq!(settings.width, 10);
+        assert_eq!(settings.depth, 128);
+        assert_eq!(settings.biomes, vec!["swamp".to_string()]);
+
+        for invalid in [
+            "cell_size: 0",
+            "continent_scale: 0.0",
+            "continent_scale: -2.0",
+            "continent_scale: .nan",
+            "land_bias: .inf",
+            "region_count: 0",
+            "region_count: 100000",
+            "river_count: 100000",
+            "width: 100000\ndepth: 100000",
+            "points_of_interest: [{kind: village, count: 100000}]",
+            "biomes: []",
+        ] {
+            let yaml: serde_yaml::Value = serde_yaml::from_str(invalid).unwrap();
+            assert!(MacroMapSettings::from_settings(&Some(yaml)).is_err(), "{}", invalid);
+        }
+        assert!(MacroMap::generate(7, &MacroMapSettings::default().size(0, 4)).is_err());
+    }
+}
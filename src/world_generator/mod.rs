@@ -1 +1,7 @@
+pub mod macro_map;
+pub mod pipeline;
+pub mod random;
 pub mod traits;
+
+#[cfg(feature = "full")]
+pub mod preview;
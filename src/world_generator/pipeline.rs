@@ -0,0 +1,428 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, marker::PhantomData};
+
+use crate::chunks::{chunk_data::ChunkData, chunk_position::ChunkPosition};
+
+use super::traits::{IWorldGenerator, WorldGeneratorSettings};
+
+/// Generation status of a chunk; every stage moves the chunk to the next status.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkStatus {
+    #[default]
+    Empty,
+    Terrain,
+    Carving,
+    Features,
+    Lighting,
+}
+
+impl ChunkStatus {
+    pub fn previous(&self) -> ChunkStatus {
+        match *self {
+            ChunkStatus::Empty => ChunkStatus::Empty,
+            ChunkStatus::Terrain => ChunkStatus::Empty,
+            ChunkStatus::Carving => ChunkStatus::Terrain,
+            ChunkStatus::Features => ChunkStatus::Carving,
+            ChunkStatus::Lighting => ChunkStatus::Features,
+        }
+    }
+}
+
+/// Read access to the neighbouring chunks while a stage is running.
+pub struct ChunkNeighbours<'a> {
+    chunks: &'a HashMap<ChunkPosition, GeneratingChunk>,
+}
+
+impl<'a> ChunkNeighbours<'a> {
+    pub fn get(&self, chunk_position: &ChunkPosition) -> Option<&'a ChunkData> {
+        self.chunks.get(chunk_position).map(|c| &c.data)
+    }
+
+    pub fn get_status(&self, chunk_position: &ChunkPosition) -> ChunkStatus {
+        match self.chunks.get(chunk_position) {
+            Some(c) => c.status,
+            None => ChunkStatus::Empty,
+        }
+    }
+}
+
+/// Single step of chunk generation.
+pub trait GenerationStage {
+    /// Status of the chunk after this stage
+    fn get_status(&self) -> ChunkStatus;
+
+    /// Chebyshev radius of neighbours which must reach
+    /// [`get_required_neighbour_status`](GenerationStage::get_required_neighbour_status) first.
+    fn get_neighbour_radius(&self) -> i64 {
+        0
+    }
+
+    /// Must be lower than the stage status
+    fn get_required_neighbour_status(&self) -> ChunkStatus {
+        self.get_status().previous()
+    }
+
+    fn process(
+        &self,
+        world_settings: &WorldGeneratorSettings,
+        chunk_position: &ChunkPosition,
+        chunk_data: &mut ChunkData,
+        neighbours: &ChunkNeighbours,
+    );
+}
+
+/// Runs [`IWorldGenerator`] as the terrain stage.
+pub struct WorldGeneratorStage<G: IWorldGenerator> {
+    _generator: PhantomData<fn() -> G>,
+}
+
+impl<G: IWorldGenerator> Default for WorldGeneratorStage<G> {
+    fn default() -> Self {
+        Self {
+            _generator: PhantomData,
+        }
+    }
+}
+
+impl<G: IWorldGenerator> GenerationStage for WorldGeneratorStage<G> {
+    fn get_status(&self) -> ChunkStatus {
+        ChunkStatus::Terrain
+    }
+
+    fn process(
+        &self,
+        world_settings: &WorldGeneratorSettings,
+        chunk_position: &ChunkPosition,
+        chunk_data: &mut ChunkData,
+        _neighbours: &ChunkNeighbours,
+    ) {
+        *chunk_data = G::generate_chunk_data(world_settings, chunk_position);
+    }
+}
+
+#[derive(Default)]
+struct GeneratingChunk {
+    status: ChunkStatus,
+    data: ChunkData,
+}
+
+/// Drives chunks through the generation stages.
+///
+/// Requested chunks are moved stage by stage; when a stage needs neighbours
+/// they are requested automatically up to the required status.
+#[derive(Default)]
+pub struct GenerationPipeline {
+    stages: Vec<Box<dyn GenerationStage>>,
+    chunks: HashMap<ChunkPosition, GeneratingChunk>,
+    requests: HashMap<ChunkPosition, ChunkStatus>,
+}
+
+impl GenerationPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stage(mut self, stage: Box<dyn GenerationStage>) -> Self {
+        if stage.get_required_neighbour_status() >= stage.get_status() {
+            panic!(
+                "Stage {:?} can't require neighbours status {:?}",
+                stage.get_status(),
+                stage.get_required_neighbour_status()
+            );
+        }
+        if self.stages.iter().any(|s| s.get_status() == stage.get_status()) {
+            panic!("Stage {:?} is already registered", stage.get_status());
+        }
+        self.stages.push(stage);
+        self.stages.sort_by_key(|s| s.get_status());
+        self
+    }
+
+    /// Status of the last stage
+    pub fn get_final_status(&self) -> ChunkStatus {
+        match self.stages.last() {
+            Some(s) => s.get_status(),
+            None => ChunkStatus::Empty,
+        }
+    }
+
+    /// Highest status of the registered stages which is not above `status`
+    fn normalize(&self, status: ChunkStatus) -> ChunkStatus {
+        self.stages
+            .iter()
+            .rev()
+            .map(|s| s.get_status())
+            .find(|s| *s <= status)
+            .unwrap_or(ChunkStatus::Empty)
+    }
+
+    /// Returns true if the request is new or raised the requested status.
+    pub fn request(&mut self, chunk_position: ChunkPosition, status: ChunkStatus) -> bool {
+        let status = self.normalize(status);
+        match self.requests.get_mut(&chunk_position) {
+            Some(target) if *target >= status => false,
+            Some(target) => {
+                *target = status;
+                true
+            }
+            None => {
+                self.requests.insert(chunk_position, status);
+                true
+            }
+        }
+    }
+
+    pub fn get_status(&self, chunk_position: &ChunkPosition) -> ChunkStatus {
+        match self.chunks.get(chunk_position) {
+            Some(c) => c.status,
+            None => ChunkStatus::Empty,
+        }
+    }
+
+    pub fn get_chunk(&self, chunk_position: &ChunkPosition) -> Option<&ChunkData> {
+        self.chunks.get(chunk_position).map(|c| &c.data)
+    }
+
+    /// Removes the chunk from the pipeline, for example after it was saved.
+    pub fn take_chunk(&mut self, chunk_position: &ChunkPosition) -> Option<(ChunkStatus, ChunkData)> {
+        self.requests.remove(chunk_position);
+        self.chunks.remove(chunk_position).map(|c| (c.status, c.data))
+    }
+
+    pub fn has_requests(&self) -> bool {
+        !self.requests.is_empty()
+    }
+
+    /// Runs up to `max_steps` stages and returns how many were run.
+    ///
+    /// Chunks are processed in a stable order so the result doesn't depend on the
+    /// order of requests.
+    pub fn tick(&mut self, world_settings: &WorldGeneratorSettings, max_steps: usize) -> usize {
+        let mut steps = 0;
+        loop {
+            let mut progressed = false;
+
+            let mut requests: Vec<(ChunkPosition, ChunkStatus)> = self.requests.iter().map(|(p, s)| (*p, *s)).collect();
+            requests.sort_by_key(|(p, s)| (*s, p.x, p.z));
+
+            for (chunk_position, target) in requests {
+                if steps >= max_steps {
+                    return steps;
+                }
+
+                let status = self.get_status(&chunk_position);
+                if status >= target {
+                    self.requests.remove(&chunk_position);
+                    continue;
+                }
+                let Some(stage_index) = self.stages.iter().position(|s| s.get_status() > status) else {
+                    self.requests.remove(&chunk_position);
+                    continue;
+                };
+
+                match self.request_neighbours(&chunk_position, stage_index) {
+                    NeighboursState::Ready => (),
+                    NeighboursState::Requested => {
+                        progressed = true;
+                        continue;
+                    }
+                    NeighboursState::Waiting => continue,
+                }
+
+                let stage = &self.stages[stage_index];
+                let mut chunk = self.chunks.remove(&chunk_position).unwrap_or_default();
+                let neighbours = ChunkNeighbours { chunks: &self.chunks };
+                stage.process(world_settings, &chunk_position, &mut chunk.data, &neighbours);
+                chunk.status = stage.get_status();
+                self.chunks.insert(chunk_position, chunk);
+
+                steps += 1;
+                progressed = true;
+            }
+
+            if !progressed {
+                return steps;
+            }
+        }
+    }
+
+    /// Requests the neighbours needed by the stage.
+    ///
+    /// A neighbour request counts as progress only when it is new or raised.
+    fn request_neighbours(&mut self, chunk_position: &ChunkPosition, stage_index: usize) -> NeighboursState {
+        let stage = &self.stages[stage_index];
+        let radius = stage.get_neighbour_radius();
+        let required = self.normalize(stage.get_required_neighbour_status());
+
+        let mut ready = true;
+        let mut requested = false;
+        for x in -radius..=radius {
+            for z in -radius..=radius {
+                if x == 0 && z == 0 {
+                    continue;
+                }
+                let neighbour = *chunk_position + ChunkPosition::new(x, z);
+                if self.get_status(&neighbour) >= required {
+                    continue;
+                }
+                ready = false;
+                requested |= self.request(neighbour, required);
+            }
+        }
+        match (ready, requested) {
+            (true, _) => NeighboursState::Ready,
+            (false, true) => NeighboursState::Requested,
+            (false, false) => NeighboursState::Waiting,
+        }
+    }
+}
+
+enum NeighboursState {
+    Ready,
+    /// Some neighbours are not ready and at least one of them was requested or raised
+    Requested,
+    /// Neighbours are not ready and all of them were already requested
+    Waiting,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::{ChunkNeighbours, ChunkStatus, GenerationPipeline, GenerationStage, NeighboursState};
+    use crate::{
+        chunks::{
+            chunk_data::{ChunkData, ChunkSectionData},
+            chunk_position::ChunkPosition,
+        },
+        world_generator::traits::WorldGeneratorSettings,
+    };
+
+    struct TestStage {
+        status: ChunkStatus,
+        radius: i64,
+        log: Rc<RefCell<Vec<(ChunkStatus, ChunkPosition)>>>,
+    }
+
+    impl GenerationStage for TestStage {
+        fn get_status(&self) -> ChunkStatus {
+            self.status
+        }
+
+        fn get_neighbour_radius(&self) -> i64 {
+            self.radius
+        }
+
+        fn process(
+            &self,
+            _world_settings: &WorldGeneratorSettings,
+            chunk_position: &ChunkPosition,
+            chunk_data: &mut ChunkData,
+            neighbours: &ChunkNeighbours,
+        ) {
+            for x in -self.radius..=self.radius {
+                for z in -self.radius..=self.radius {
+                    let neighbour = *chunk_position + ChunkPosition::new(x, z);
+                    if neighbour != *chunk_position {
+                        assert!(neighbours.get_status(&neighbour) >= ChunkStatus::Terrain);
+                    }
+                }
+            }
+            if self.status == ChunkStatus::Terrain {
+                chunk_data.push_section(ChunkSectionData::default());
+            }
+            self.log.borrow_mut().push((self.status, *chunk_position));
+        }
+    }
+
+    fn pipeline(log: &Rc<RefCell<Vec<(ChunkStatus, ChunkPosition)>>>) -> GenerationPipeline {
+        GenerationPipeline::new()
+            .stage(Box::new(TestStage {
+                status: ChunkStatus::Features,
+                radius: 1,
+                log: log.clone(),
+            }))
+            .stage(Box::new(TestStage {
+                status: ChunkStatus::Terrain,
+                radius: 0,
+                log: log.clone(),
+            }))
+    }
+
+    #[test]
+    fn test_pipeline_neighbours() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut pipeline = pipeline(&log);
+        let center = ChunkPosition::new(0, 0);
+        pipeline.request(center, ChunkStatus::Lighting);
+
+        let steps = pipeline.tick(&WorldGeneratorSettings::default(), 100);
+        assert_eq!(steps, 10);
+        assert!(!pipeline.has_requests());
+
+        assert_eq!(pipeline.get_final_status(), ChunkStatus::Features);
+        assert_eq!(pipeline.get_status(&center), ChunkStatus::Features);
+        assert_eq!(pipeline.get_status(&ChunkPosition::new(1, 1)), ChunkStatus::Terrain);
+        assert_eq!(pipeline.get_status(&ChunkPosition::new(2, 0)), ChunkStatus::Empty);
+        assert_eq!(pipeline.get_chunk(&center).unwrap().len(), 1);
+
+        let log = log.borrow();
+        assert_eq!(log.last().unwrap(), &(ChunkStatus::Features, center));
+    }
+
+    #[test]
+    fn test_pipeline_max_steps() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut pipeline = pipeline(&log);
+        pipeline.request(ChunkPosition::new(5, 5), ChunkStatus::Features);
+
+        assert_eq!(pipeline.tick(&WorldGeneratorSettings::default(), 4), 4);
+        assert!(pipeline.has_requests());
+        assert_eq!(pipeline.tick(&WorldGeneratorSettings::default(), 100), 6);
+
+        let (status, _chunk_data) = pipeline.take_chunk(&ChunkPosition::new(5, 5)).unwrap();
+        assert_eq!(status, ChunkStatus::Features);
+        assert_eq!(pipeline.get_status(&ChunkPosition::new(5, 5)), ChunkStatus::Empty);
+    }
+
+    #[test]
+    fn test_pipeline_repeated_request() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut pipeline = pipeline(&log);
+        let center = ChunkPosition::new(0, 0);
+        assert!(pipeline.request(center, ChunkStatus::Terrain));
+        assert!(!pipeline.request(center, ChunkStatus::Terrain));
+        assert!(pipeline.request(center, ChunkStatus::Features));
+        assert!(!pipeline.request(center, ChunkStatus::Terrain));
+
+        // Neighbours already requested at the same stage are not a progress
+        let features = 1;
+        assert!(matches!(
+            pipeline.request_neighbours(&center, features),
+            NeighboursState::Requested
+        ));
+        assert!(matches!(
+            pipeline.request_neighbours(&center, features),
+            NeighboursState::Waiting
+        ));
+
+        // Waiting chunk is generated after its neighbours
+        assert_eq!(pipeline.tick(&WorldGeneratorSettings::default(), 100), 10);
+        assert!(matches!(
+            pipeline.request_neighbours(&center, features),
+            NeighboursState::Ready
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_pipeline_duplicate_stage() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        pipeline(&log).stage(Box::new(TestStage {
+            status: ChunkStatus::Terrain,
+            radius: 0,
+            log: log.clone(),
+        }));
+    }
+}
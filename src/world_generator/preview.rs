@@ -0,0 +1,365 @@
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    blocks::block_type::{BlockColor, BlockType},
+    chunks::{
+        block_position::ChunkBlockPosition,
+        chunk_data::{BlockIndexType, ChunkData},
+        chunk_position::ChunkPosition,
+    },
+    utils::debug::{info::DebugInfo, runtime_storage::RuntimeStorage},
+    CHUNK_SIZE,
+};
+
+use super::traits::{IWorldGenerator, WorldGeneratorSettings};
+
+const SPAN_GENERATE: &str = "world_generator::generate_chunk";
+const SPAN_SCAN: &str = "world_generator::scan_columns";
+
+/// Largest preview side in chunks
+pub const MAX_PREVIEW_CHUNKS: i64 = 256;
+
+const UNKNOWN_COLOR: BlockColor = [255, 0, 255];
+const EMPTY_COLOR: BlockColor = [0, 0, 0];
+
+/// Highest non-empty block of a single column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TopBlock {
+    pub id: BlockIndexType,
+    pub y: u32,
+}
+
+/// Runs a world generator over a rectangular region of chunks without starting the game.
+///
+/// Collects timings of every chunk and the top block of every column,
+/// which is enough to render a map preview or to compare generation output in tests.
+pub struct GeneratorPreview {
+    from: ChunkPosition,
+    to: ChunkPosition,
+    chunk_timings: Vec<(ChunkPosition, Duration)>,
+    runtime_storage: RuntimeStorage,
+    top_blocks: Vec<Option<TopBlock>>,
+}
+
+impl GeneratorPreview {
+    /// Generates all chunks between `from` and `to` (both inclusive).
+    ///
+    /// Region sides must not be longer than [`MAX_PREVIEW_CHUNKS`].
+    pub fn run<G: IWorldGenerator>(
+        settings: &WorldGeneratorSettings,
+        from: &ChunkPosition,
+        to: &ChunkPosition,
+    ) -> Result<Self, String> {
+        let (from, to) = (
+            ChunkPosition::new(from.x.min(to.x), from.z.min(to.z)),
+            ChunkPosition::new(from.x.max(to.x), from.z.max(to.z)),
+        );
+        let is_too_large = |min: i64, max: i64| max.checked_sub(min).is_none_or(|d| d >= MAX_PREVIEW_CHUNKS);
+        if is_too_large(from.x, to.x) || is_too_large(from.z, to.z) {
+            return Err(format!(
+                "&cpreview region from &4{} &cto &4{} &cis larger than &4{} &cchunks",
+                from, to, MAX_PREVIEW_CHUNKS
+            ));
+        }
+
+        let mut preview = Self {
+            from,
+            to,
+            chunk_timings: Default::default(),
+            runtime_storage: RuntimeStorage::new(),
+            top_blocks: Default::default(),
+        };
+        let (width, depth) = preview.get_size();
+        preview.top_blocks = vec![None; width as usize * depth as usize];
+
+        for chunk_z in from.z..=to.z {
+            for chunk_x in from.x..=to.x {
+                let chunk_position = ChunkPosition::new(chunk_x, chunk_z);
+
+                let now = Instant::now();
+                let chunk_data = G::generate_chunk_data(settings, &chunk_position);
+                let elapsed = now.elapsed();
+                preview.runtime_storage.push(SPAN_GENERATE, elapsed);
+                preview.chunk_timings.push((chunk_position, elapsed));
+
+                let now = Instant::now();
+                preview.scan_columns(&chunk_position, &chunk_data);
+                preview.runtime_storage.push(SPAN_SCAN, now.elapsed());
+            }
+        }
+        Ok(preview)
+    }
+
+    fn scan_columns(&mut self, chunk_position: &ChunkPosition, chunk_data: &ChunkData) {
+        let (width, _depth) = self.get_size();
+        for x in 0..CHUNK_SIZE {
+            for z in 0..CHUNK_SIZE {
+                let map_x = (chunk_position.x - self.from.x) as u32 * CHUNK_SIZE as u32 + x as u32;
+                let map_z = (chunk_position.z - self.from.z) as u32 * CHUNK_SIZE as u32 + z as u32;
+                self.top_blocks[map_z as usize * width as usize + map_x as usize] = find_top_block(chunk_data, x, z);
+            }
+        }
+    }
+
+    /// Preview size in blocks (width by x, depth by z); sides are limited by [`MAX_PREVIEW_CHUNKS`]
+    pub fn get_size(&self) -> (u32, u32) {
+        let width = (self.to.x - self.from.x + 1) as u32 * CHUNK_SIZE as u32;
+        let depth = (self.to.z - self.from.z + 1) as u32 * CHUNK_SIZE as u32;
+        (width, depth)
+    }
+
+    pub fn get_chunk_timings(&self) -> &Vec<(ChunkPosition, Duration)> {
+        &self.chunk_timings
+    }
+
+    pub fn get_runtime_storage(&self) -> &RuntimeStorage {
+        &self.runtime_storage
+    }
+
+    /// Top block of the column; coordinates are relative to the region minimum block.
+    ///
+    /// `None` if the column is empty or out of the preview size.
+    pub fn get_top_block(&self, x: u32, z: u32) -> Option<&TopBlock> {
+        let (width, depth) = self.get_size();
+        if x >= width || z >= depth {
+            return None;
+        }
+        self.top_blocks[z as usize * width as usize + x as usize].as_ref()
+    }
+
+    pub fn get_top_blocks(&self) -> &Vec<Option<TopBlock>> {
+        &self.top_blocks
+    }
+
+    pub fn get_debug_info(&self) -> DebugInfo {
+        let total: Duration = self.chunk_timings.iter().map(|(_, d)| *d).sum();
+        let min = self.chunk_timings.iter().map(|(_, d)| *d).min().unwrap_or_default();
+        let max = self.chunk_timings.iter().map(|(_, d)| *d).max().unwrap_or_default();
+        let avg = match self.chunk_timings.len() {
+            0 => Duration::ZERO,
+            len => total / len as u32,
+        };
+        DebugInfo::new()
+            .insert("chunks", self.chunk_timings.len())
+            .insert("total", total)
+            .insert("avg", avg)
+            .insert("min", min)
+            .insert("max", max)
+    }
+
+    /// Renders a top-down map using the block map colors, shaded by height.
+    ///
+    /// Blocks without `map_color` are drawn magenta, empty columns black.
+    pub fn render(&self, map_colors: &BTreeMap<BlockIndexType, BlockColor>) -> PreviewImage {
+        let (width, height) = self.get_size();
+        let (min_y, max_y) = self
+            .top_blocks
+            .iter()
+            .flatten()
+            .fold((u32::MAX, 0), |(min, max), b| (min.min(b.y), max.max(b.y)));
+
+        let pixels = self
+            .top_blocks
+            .iter()
+            .map(|top| {
+                let Some(top) = top else {
+                    return EMPTY_COLOR;
+                };
+                let color = map_colors.get(&top.id).cloned().unwrap_or(UNKNOWN_COLOR);
+                let shade = match max_y > min_y {
+                    true => 0.6 + 0.4 * (top.y - min_y) as f32 / (max_y - min_y) as f32,
+                    false => 1.0,
+                };
+                color.map(|c| (c as f32 * shade) as u8)
+            })
+            .collect();
+        PreviewImage { width, height, pixels }
+    }
+}
+
+/// Collects map colors of the block types by their ids.
+pub fn get_map_colors<'a>(
+    block_id_map: &BTreeMap<BlockIndexType, String>,
+    block_types: impl Iterator<Item = &'a BlockType>,
+) -> BTreeMap<BlockIndexType, BlockColor> {
+    let mut colors = BTreeMap::new();
+    for block_type in block_types {
+        let Some(color) = block_type.get_map_color() else {
+            continue;
+        };
+        for (id, slug) in block_id_map.iter() {
+            if slug == block_type.get_slug() {
+                colors.insert(*id, *color);
+            }
+        }
+    }
+    colors
+}
+
+fn find_top_block(chunk_data: &ChunkData, x: u8, z: u8) -> Option<TopBlock> {
+    for section in (0..chunk_data.len()).rev() {
+        let section_data = chunk_data.get(section)?;
+        for y in (0..CHUNK_SIZE).rev() {
+            if let Some(block) = section_data.get(&ChunkBlockPosition::new(x, y, z)) {
+                return Some(TopBlock {
+                    id: block.get_id(),
+                    y: section as u32 * CHUNK_SIZE as u32 + y as u32,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// RGB image of the generator preview
+pub struct PreviewImage {
+    width: u32,
+    height: u32,
+    pixels: Vec<BlockColor>,
+}
+
+impl PreviewImage {
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn get_pixel(&self, x: u32, y: u32) -> &BlockColor {
+        &self.pixels[y as usize * self.width as usize + x as usize]
+    }
+
+    /// Encodes the image as 8-bit RGB PNG
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity((self.width as usize * 3 + 1) * self.height as usize);
+        for row in self.pixels.chunks(self.width as usize) {
+            // Filter type "None"
+            raw.push(0);
+            for pixel in row {
+                raw.extend_from_slice(pixel);
+            }
+        }
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&self.width.to_be_bytes());
+        header.extend_from_slice(&self.height.to_be_bytes());
+        // Bit depth 8, color type RGB, default compression, filter and interlace
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        let mut png = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+        write_png_chunk(&mut png, b"IHDR", &header);
+        write_png_chunk(&mut png, b"IDAT", &miniz_oxide::deflate::compress_to_vec_zlib(&raw, 6));
+        write_png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+fn write_png_chunk(png: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(chunk_type);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffffffff_u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb88320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{crc32, GeneratorPreview, TopBlock, MAX_PREVIEW_CHUNKS};
+    use crate::{
+        chunks::{
+            block_position::ChunkBlockPosition,
+            chunk_data::{BlockDataInfo, ChunkData, ChunkSectionData},
+            chunk_position::ChunkPosition,
+        },
+        world_generator::traits::{IWorldGenerator, WorldGeneratorSettings},
+        CHUNK_SIZE,
+    };
+
+    /// Flat ground, one block higher for every chunk by x
+    struct StepsGenerator;
+
+    impl IWorldGenerator for StepsGenerator {
+        fn generate_chunk_data(_world_settings: &WorldGeneratorSettings, chunk_position: &ChunkPosition) -> ChunkData {
+            let mut chunk_data = ChunkData::default();
+            let mut section = ChunkSectionData::default();
+            for x in 0..CHUNK_SIZE {
+                for z in 0..CHUNK_SIZE {
+                    let y = (chunk_position.x + 2) as u8;
+                    section.insert(&ChunkBlockPosition::new(x, y, z), BlockDataInfo::create(1));
+                }
+            }
+            chunk_data.push_section(section);
+            chunk_data
+        }
+    }
+
+    #[test]
+    fn test_preview_top_blocks() {
+        let settings = WorldGeneratorSettings::default();
+        let preview =
+            GeneratorPreview::run::<StepsGenerator>(&settings, &ChunkPosition::new(0, 0), &ChunkPosition::new(-1, 0))
+                .unwrap();
+
+        assert_eq!(preview.get_size(), (32, 16));
+        assert_eq!(preview.get_chunk_timings().len(), 2);
+        assert_eq!(preview.get_top_block(0, 0), Some(&TopBlock { id: 1, y: 1 }));
+        assert_eq!(preview.get_top_block(31, 15), Some(&TopBlock { id: 1, y: 2 }));
+        assert_eq!(preview.get_top_block(32, 0), None);
+        assert_eq!(preview.get_top_block(0, 16), None);
+        assert_eq!(preview.get_top_block(u32::MAX, u32::MAX), None);
+
+        let debug_info = preview.get_debug_info();
+        assert_eq!(debug_info.get("chunks").unwrap().as_f64(), Some(2.0));
+    }
+
+    #[test]
+    fn test_preview_render() {
+        let settings = WorldGeneratorSettings::default();
+        let preview =
+            GeneratorPreview::run::<StepsGenerator>(&settings, &ChunkPosition::new(-1, 0), &ChunkPosition::new(0, 0))
+                .unwrap();
+
+        let mut colors = BTreeMap::new();
+        colors.insert(1, [100, 200, 50]);
+        let image = preview.render(&colors);
+        assert_eq!(image.get_pixel(31, 0), &[100, 200, 50]);
+        assert_eq!(image.get_pixel(0, 0), &[60, 120, 30]);
+
+        let png = image.to_png();
+        assert_eq!(&png[1..4], b"PNG");
+    }
+
+    #[test]
+    fn test_preview_too_large() {
+        let settings = WorldGeneratorSettings::default();
+        let (from, to) = (ChunkPosition::new(0, 0), ChunkPosition::new(MAX_PREVIEW_CHUNKS, 0));
+        assert!(GeneratorPreview::run::<StepsGenerator>(&settings, &from, &to).is_err());
+        let (from, to) = (ChunkPosition::new(0, i64::MIN), ChunkPosition::new(0, i64::MAX));
+        assert!(GeneratorPreview::run::<StepsGenerator>(&settings, &from, &to).is_err());
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"IEND"), 0xae426082);
+    }
+}
@@ -0,0 +1,175 @@
+use rand::RngCore;
+
+use crate::{
+    blocks::block_info::BlockFace,
+    chunks::{block_position::BlockPosition, chunk_position::ChunkPosition},
+};
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+const GOLDEN_GAMMA: u64 = 0x9e3779b97f4a7c15;
+
+/// Stable 64-bit FNV-1a hash.
+///
+/// `DefaultHasher` output may change between Rust versions,
+/// so it can't be used for anything that affects generated chunks.
+pub fn stable_hash(data: &[u8]) -> u64 {
+    let mut hash = FNV_OFFSET;
+    for byte in data {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(GOLDEN_GAMMA);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// Seeded random source for world generators and decorators.
+///
+/// The sequence depends only on `(world_seed, position, salt)` and uses
+/// wrapping integer math only, so every platform, native or WASM, produces
+/// the same chunk. Different `salt` values give independent sequences for
+/// the same chunk (for example "trees" and "ores").
+///
+/// Implements [`RngCore`], so [`rand::Rng`] helpers are available too.
+#[derive(Clone, Debug)]
+pub struct ChunkRandom {
+    state: u64,
+}
+
+impl ChunkRandom {
+    pub fn create(world_seed: u64, chunk_position: &ChunkPosition, salt: &str) -> Self {
+        let mut state = splitmix64(world_seed ^ stable_hash(salt.as_bytes()));
+        state = splitmix64(state ^ chunk_position.x as u64);
+        state = splitmix64(state ^ chunk_position.z as u64);
+        Self { state }
+    }
+
+    /// Random source for a single block, for decisions which must not depend
+    /// on the order in which blocks of the chunk are processed.
+    pub fn for_block(world_seed: u64, block_position: &BlockPosition, salt: &str) -> Self {
+        let mut state = splitmix64(world_seed ^ stable_hash(salt.as_bytes()));
+        state = splitmix64(state ^ block_position.get_x() as u64);
+        state = splitmix64(state ^ block_position.get_y() as u64);
+        state = splitmix64(state ^ block_position.get_z() as u64);
+        Self { state }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let value = splitmix64(self.state);
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        value
+    }
+
+    /// Returns a float in `[0.0, 1.0)`
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1_u64 << 24) as f32
+    }
+
+    /// Returns a number in `[min, max)`
+    pub fn range(&mut self, min: i64, max: i64) -> i64 {
+        if max <= min {
+            return min;
+        }
+        let span = max.wrapping_sub(min) as u64;
+        let offset = ((self.next_u64() as u128 * span as u128) >> 64) as u64;
+        min.wrapping_add(offset as i64)
+    }
+
+    /// Returns true with the given probability from `0.0` to `1.0`
+    pub fn chance(&mut self, probability: f32) -> bool {
+        self.next_f32() < probability
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+        items.get(self.range(0, items.len() as i64) as usize)
+    }
+
+    pub fn face(&mut self) -> BlockFace {
+        match self.range(0, 4) {
+            0 => BlockFace::East,
+            1 => BlockFace::North,
+            2 => BlockFace::South,
+            _ => BlockFace::West,
+        }
+    }
+}
+
+impl RngCore for ChunkRandom {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        ChunkRandom::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChunkRandom;
+    use crate::chunks::{block_position::BlockPosition, chunk_position::ChunkPosition};
+
+    #[test]
+    fn test_chunk_random_is_deterministic() {
+        let mut a = ChunkRandom::create(42, &ChunkPosition::new(-3, 7), "trees");
+        let mut b = ChunkRandom::create(42, &ChunkPosition::new(-3, 7), "trees");
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_chunk_random_differs() {
+        let a = ChunkRandom::create(42, &ChunkPosition::new(0, 1), "trees").next_u64();
+        let b = ChunkRandom::create(42, &ChunkPosition::new(1, 0), "trees").next_u64();
+        let c = ChunkRandom::create(42, &ChunkPosition::new(0, 1), "ores").next_u64();
+        let d = ChunkRandom::create(43, &ChunkPosition::new(0, 1), "trees").next_u64();
+        assert_ne!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(a, d);
+    }
+
+    #[test]
+    fn test_chunk_random_is_stable() {
+        // Changing these values changes every generated world
+        let mut r = ChunkRandom::create(1, &ChunkPosition::new(2, 3), "test");
+        assert_eq!(r.next_u64(), 14375465916509285580);
+
+        let mut r = ChunkRandom::for_block(1, &BlockPosition::new(2, 3, 4), "test");
+        assert_eq!(r.next_u64(), 4531121741666831163);
+    }
+
+    #[test]
+    fn test_chunk_random_range() {
+        let mut r = ChunkRandom::create(5, &ChunkPosition::zero(), "range");
+        for _ in 0..1000 {
+            let v = r.range(-5, 5);
+            assert!((-5..5).contains(&v));
+            let f = r.next_f32();
+            assert!((0.0..1.0).contains(&f));
+        }
+        assert_eq!(r.range(3, 3), 3);
+        assert!(r.choose::<u8>(&[]).is_none());
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 
+use super::random::ChunkRandom;
 use crate::{chunks::{
     chunk_data::{ChunkData, WorldMacroData},
     chunk_position::ChunkPosition,
@@ -43,6 +44,11 @@ impl WorldGeneratorSettings {
     pub fn get_world_macro_data(&self) -> &WorldMacroData {
         &self.world_macro_data
     }
+
+    /// Deterministic random source for the chunk; see [`ChunkRandom`].
+    pub fn get_chunk_random(&self, chunk_position: &ChunkPosition, salt: &str) -> ChunkRandom {
+        ChunkRandom::create(self.seed, chunk_position, salt)
+    }
 }
 
 impl From<&WorldStorageData> for WorldGeneratorSettings {
@@ -0,0 +1,7 @@
+#![cfg(feature = "wasm-plugin")]
+
+#[test]
+fn event_handler_compile_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
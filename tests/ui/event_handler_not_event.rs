@@ -0,0 +1,9 @@
+use common::event_handler;
+
+#[derive(serde::Deserialize)]
+struct NotAnEvent;
+
+#[event_handler]
+pub fn on_custom(_event: NotAnEvent) {}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use common::{event_handler, plugin_api::events::player_join::PlayerJoinEvent};
+
+struct NotAService;
+
+#[event_handler]
+pub fn on_player_join(_event: PlayerJoinEvent, _service: NotAService) {}
+
+fn main() {}